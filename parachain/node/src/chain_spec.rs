@@ -1,5 +1,9 @@
 use cumulus_primitives_core::ParaId;
-use parachain_runtime::{AccountId, AuraId, AssetsConfig, Signature, EXISTENTIAL_DEPOSIT};
+use parachain_runtime::{
+	AccountId, AuraId, AssetsConfig, Balance, DexAssetId, DexConfig, KittiesConfig, Permill, Signature,
+	EXISTENTIAL_DEPOSIT,
+};
+use pallet_kitties::Gender;
 use sc_chain_spec::{ChainSpecExtension, ChainSpecGroup};
 use sc_service::ChainType;
 use serde::{Deserialize, Serialize};
@@ -61,7 +65,82 @@ pub fn template_session_keys(keys: AuraId) -> parachain_runtime::SessionKeys {
 	parachain_runtime::SessionKeys { aura: keys }
 }
 
+/// The native balance of every endowed account in genesis
+pub const ENDOWMENT: Balance = 1 << 60;
+
+/// The well known development accounts, endowed with [`ENDOWMENT`] in genesis
+pub fn endowed_accounts() -> Vec<AccountId> {
+	vec![
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		get_account_id_from_seed::<sr25519::Public>("Bob"),
+		get_account_id_from_seed::<sr25519::Public>("Charlie"),
+		get_account_id_from_seed::<sr25519::Public>("Dave"),
+		get_account_id_from_seed::<sr25519::Public>("Eve"),
+		get_account_id_from_seed::<sr25519::Public>("Ferdie"),
+		get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+	]
+}
+
+/// The assets and DEX pools put into genesis, see [`crate::manifest`] to load them from a file.
+#[derive(Clone)]
+pub struct DexGenesis {
+	/// (id, owner, is_sufficient, min_balance)
+	pub assets: Vec<(u32, AccountId, bool, Balance)>,
+	/// (id, name, symbol, decimals)
+	pub metadata: Vec<(u32, Vec<u8>, Vec<u8>, u8)>,
+	/// (id, account, balance)
+	pub accounts: Vec<(u32, AccountId, Balance)>,
+	/// ((amount, asset), (amount, asset), liquidity provider, fee tier)
+	pub pools: Vec<((Balance, DexAssetId), (Balance, DexAssetId), AccountId, Permill)>,
+}
+
+/// The TKA, TKB and TAB assets with the TKA/TKB, UNIT/TKA and UNIT/TKB markets provided by Alice,
+/// at 1 TKA = 1 TKB = 1 UNIT. The assets have 8 decimals and UNIT has 12.
+///
+/// The pool accounts of the UNIT markets also receive the existential deposit from Alice, on top
+/// of their native reserves, so the reserves can be traded below `EXISTENTIAL_DEPOSIT`.
+pub fn default_dex_genesis() -> DexGenesis {
+	let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+	let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+	let fee = Permill::from_parts(3_000);
+	DexGenesis {
+		assets: vec![
+			(1u32, alice.clone(), true, 1),
+			(2u32, alice.clone(), true, 1),
+			(3u32, alice.clone(), true, 1),
+		],
+		metadata: vec![
+			(1u32, b"TOKEN_A".to_vec(), b"TKA".to_vec(), 8u8),
+			(2u32, b"TOKEN_B".to_vec(), b"TKB".to_vec(), 8u8),
+			(3u32, b"TOKA_TOKB".to_vec(), b"TAB".to_vec(), 8u8),
+		],
+		accounts: vec![
+			(1u32, alice.clone(), 1_000_000),
+			(1u32, bob.clone(), 1_000_000),
+			(2u32, alice.clone(), 1_000_000),
+			(2u32, bob.clone(), 1_000_000),
+			(3u32, alice.clone(), 1_000_000),
+			(3u32, bob, 1_000_000),
+		],
+		pools: vec![
+			((250_000, DexAssetId::Asset(1)), (250_000, DexAssetId::Asset(2)), alice.clone(), fee),
+			((250_000 * 10_000, DexAssetId::Native), (250_000, DexAssetId::Asset(1)), alice.clone(), fee),
+			((250_000 * 10_000, DexAssetId::Native), (250_000, DexAssetId::Asset(2)), alice, fee),
+		],
+	}
+}
+
 pub fn development_config() -> ChainSpec {
+	development_config_with(default_dex_genesis())
+}
+
+/// The development chain spec with the given assets and DEX pools in genesis
+pub fn development_config_with(dex: DexGenesis) -> ChainSpec {
 	// Give your base currency a unit name and decimal places
 	let mut properties = sc_chain_spec::Properties::new();
 	properties.insert("tokenSymbol".into(), "UNIT".into());
@@ -87,21 +166,9 @@ pub fn development_config() -> ChainSpec {
 						get_collator_keys_from_seed("Bob"),
 					),
 				],
-				vec![
-					get_account_id_from_seed::<sr25519::Public>("Alice"),
-					get_account_id_from_seed::<sr25519::Public>("Bob"),
-					get_account_id_from_seed::<sr25519::Public>("Charlie"),
-					get_account_id_from_seed::<sr25519::Public>("Dave"),
-					get_account_id_from_seed::<sr25519::Public>("Eve"),
-					get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-				],
+				endowed_accounts(),
 				1000.into(),
+				dex.clone(),
 			)
 		},
 		Vec::new(),
@@ -117,6 +184,11 @@ pub fn development_config() -> ChainSpec {
 }
 
 pub fn local_testnet_config() -> ChainSpec {
+	local_testnet_config_with(default_dex_genesis())
+}
+
+/// The local testnet chain spec with the given assets and DEX pools in genesis
+pub fn local_testnet_config_with(dex: DexGenesis) -> ChainSpec {
 	// Give your base currency a unit name and decimal places
 	let mut properties = sc_chain_spec::Properties::new();
 	properties.insert("tokenSymbol".into(), "UNIT".into());
@@ -142,21 +214,9 @@ pub fn local_testnet_config() -> ChainSpec {
 						get_collator_keys_from_seed("Bob"),
 					),
 				],
-				vec![
-					get_account_id_from_seed::<sr25519::Public>("Alice"),
-					get_account_id_from_seed::<sr25519::Public>("Bob"),
-					get_account_id_from_seed::<sr25519::Public>("Charlie"),
-					get_account_id_from_seed::<sr25519::Public>("Dave"),
-					get_account_id_from_seed::<sr25519::Public>("Eve"),
-					get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-				],
+				endowed_accounts(),
 				1000.into(),
+				dex.clone(),
 			)
 		},
 		// Bootnodes
@@ -181,6 +241,7 @@ fn testnet_genesis(
 	invulnerables: Vec<(AccountId, AuraId)>,
 	endowed_accounts: Vec<AccountId>,
 	id: ParaId,
+	dex: DexGenesis,
 ) -> parachain_runtime::GenesisConfig {
 	parachain_runtime::GenesisConfig {
 		system: parachain_runtime::SystemConfig {
@@ -189,7 +250,7 @@ fn testnet_genesis(
 				.to_vec(),
 		},
 		balances: parachain_runtime::BalancesConfig {
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
+			balances: endowed_accounts.iter().cloned().map(|k| (k, ENDOWMENT)).collect(),
 		},
 		parachain_info: parachain_runtime::ParachainInfoConfig { parachain_id: id },
 		collator_selection: parachain_runtime::CollatorSelectionConfig {
@@ -217,28 +278,19 @@ fn testnet_genesis(
 		polkadot_xcm: parachain_runtime::PolkadotXcmConfig {
 			safe_xcm_version: Some(SAFE_XCM_VERSION),
 		},
-		// Ideally this is replicated in pallet mocks - this way we can start from genesis for every pallet's test and operate from that
-		assets: {
-			AssetsConfig {
-				assets: vec![
-					(1u32, get_account_id_from_seed::<sr25519::Public>("Alice"), true, 1),
-					(2u32, get_account_id_from_seed::<sr25519::Public>("Alice"), true, 1),
-					(3u32, get_account_id_from_seed::<sr25519::Public>("Alice"), true, 1),
-				],
-				metadata: vec![
-					(1u32, b"TOKEN_A".to_vec(), b"TKA".to_vec(), 8u8),
-					(2u32, b"TOKEN_B".to_vec(), b"TKB".to_vec(), 8u8),
-					(3u32, b"TOKA_TOKB".to_vec(), b"TAB".to_vec(), 8u8),
-				],
-				accounts: vec![
-					(1u32, get_account_id_from_seed::<sr25519::Public>("Alice"), 1_000_000),
-					(1u32, get_account_id_from_seed::<sr25519::Public>("Bob"), 1_000_000),
-					(2u32, get_account_id_from_seed::<sr25519::Public>("Alice"), 1_000_000),
-					(2u32, get_account_id_from_seed::<sr25519::Public>("Bob"), 1_000_000),
-					(3u32, get_account_id_from_seed::<sr25519::Public>("Alice"), 1_000_000),
-					(3u32, get_account_id_from_seed::<sr25519::Public>("Bob"), 1_000_000),
-				],
-			}
+		assets: AssetsConfig {
+			assets: dex.assets,
+			metadata: dex.metadata,
+			accounts: dex.accounts,
+		},
+		dex: DexConfig { liquidity_pools: dex.pools },
+		// A breeding pair for Alice and a kitty for Bob
+		kitties: KittiesConfig {
+			kitties: vec![
+				(get_account_id_from_seed::<sr25519::Public>("Alice"), sp_core::blake2_128(b"Alice/kitty/0"), Gender::Male),
+				(get_account_id_from_seed::<sr25519::Public>("Alice"), sp_core::blake2_128(b"Alice/kitty/1"), Gender::Female),
+				(get_account_id_from_seed::<sr25519::Public>("Bob"), sp_core::blake2_128(b"Bob/kitty/0"), Gender::Female),
+			],
 		},
 	}
 }
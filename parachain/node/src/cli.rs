@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{fmt, path::PathBuf, str::FromStr};
 
 /// Sub-commands supported by the collator.
 #[derive(Debug, clap::Subcommand)]
@@ -6,6 +6,9 @@ pub enum Subcommand {
 	/// Build a chain specification.
 	BuildSpec(sc_cli::BuildSpecCmd),
 
+	/// Build a chain specification with the assets and DEX pools of a manifest.
+	BuildDexSpec(BuildDexSpecCmd),
+
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
 
@@ -37,6 +40,93 @@ pub enum Subcommand {
 
 	/// Try some testing command against a specified runtime state.
 	TryRuntime(try_runtime_cli::TryRuntimeCmd),
+
+	/// Inspect the DEX pools in the database of a stopped node.
+	Dex(DexCmd),
+}
+
+/// The `build-dex-spec` command, `build-spec` with the assets and DEX pools of a manifest.
+#[derive(Debug, clap::Parser)]
+pub struct BuildDexSpecCmd {
+	/// TOML (or `.json`) manifest of the genesis assets and pools, replacing the default ones.
+	///
+	/// Only the `dev` and `local` chains can be built from a manifest.
+	#[clap(long, parse(from_os_str))]
+	pub manifest: PathBuf,
+
+	#[clap(flatten)]
+	pub base: sc_cli::BuildSpecCmd,
+}
+
+/// The `dex` command, querying the DEX runtime API at a block of the node database.
+#[derive(Debug, clap::Parser)]
+pub struct DexCmd {
+	#[clap(subcommand)]
+	pub action: DexAction,
+
+	/// Hash of the block to query, the best block by default.
+	///
+	/// The state of older blocks is only kept with `--state-pruning archive`.
+	#[clap(long, global = true)]
+	pub at: Option<parachain_runtime::Hash>,
+
+	/// Print a table or JSON.
+	#[clap(long, arg_enum, default_value = "table", global = true)]
+	pub output: OutputFormat,
+
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+
+	#[clap(flatten)]
+	pub pruning_params: sc_cli::PruningParams,
+}
+
+/// What the `dex` command prints. Assets are `UNIT` for the native currency or an asset id,
+/// accounts are SS58 addresses or development seeds such as `Alice`.
+#[derive(Debug, clap::Subcommand)]
+pub enum DexAction {
+	/// All pools with their fee tier, LP token and reserves.
+	Pools,
+
+	/// The reserves of the pool of two assets.
+	Reserves {
+		a: String,
+		b: String,
+
+		/// Fee tier of the pool in parts per million, the default tier if not given.
+		#[clap(long)]
+		fee: Option<u32>,
+	},
+
+	/// The liquidity an account provides to every pool, in every fee tier.
+	Positions {
+		account: String,
+	},
+
+	/// The reserves and last trade of the pool of two assets after every block which changed
+	/// them, up to `--at`.
+	History {
+		a: String,
+		b: String,
+
+		/// Number of the first block to look at.
+		#[clap(long, default_value = "0")]
+		from: u32,
+
+		/// Fee tier of the pool in parts per million, the default tier if not given.
+		#[clap(long)]
+		fee: Option<u32>,
+	},
+}
+
+/// Output format of the `dex` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+pub enum OutputFormat {
+	Table,
+	Json,
 }
 
 #[derive(Debug, clap::Parser)]
@@ -62,6 +152,16 @@ pub struct Cli {
 	#[clap(long)]
 	pub no_hardware_benchmarks: bool,
 
+	/// How the `--dev` node seals blocks: `instant` to seal a block for every transaction,
+	/// `manual` to only seal blocks requested with `engine_createBlock`, or an interval in
+	/// milliseconds.
+	///
+	/// The `--dev` node runs without a relay chain, the parachain inherents are mocked. Blocks
+	/// can be sealed with `engine_createBlock` in every mode, and `dev_timeTravel` moves the
+	/// timestamp of the next block forward.
+	#[clap(long, default_value = "instant")]
+	pub sealing: Sealing,
+
 	/// Relay chain arguments
 	#[clap(raw = true)]
 	pub relay_chain_args: Vec<String>,
@@ -91,3 +191,41 @@ impl RelayChainCli {
 		Self { base_path, chain_id, base: clap::Parser::parse_from(relay_chain_args) }
 	}
 }
+
+/// Block sealing mode of the standalone `--dev` node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal a block as soon as a transaction enters the pool.
+	Instant,
+	/// Only seal blocks requested over RPC.
+	Manual,
+	/// Seal a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"instant" => Ok(Self::Instant),
+			"manual" => Ok(Self::Manual),
+			millis => millis
+				.parse()
+				.ok()
+				.filter(|millis| *millis > 0)
+				.map(Self::Interval)
+				.ok_or_else(|| format!("Invalid sealing mode `{}`, expected `instant`, `manual` or an interval in milliseconds", s)),
+		}
+	}
+}
+
+impl fmt::Display for Sealing {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Instant => write!(f, "instant"),
+			Self::Manual => write!(f, "manual"),
+			Self::Interval(millis) => write!(f, "every {}ms", millis),
+		}
+	}
+}
@@ -20,6 +20,7 @@ use sp_runtime::traits::{AccountIdConversion, Block as BlockT};
 use crate::{
 	chain_spec,
 	cli::{Cli, RelayChainCli, Subcommand},
+	manifest::Manifest,
 	service::{new_partial, TemplateRuntimeExecutor},
 };
 
@@ -139,6 +140,18 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
 		},
+		Some(Subcommand::BuildDexSpec(cmd)) => {
+			let runner = cli.create_runner(&cmd.base)?;
+			runner.sync_run(|config| {
+				let dex = Manifest::from_file(&cmd.manifest)?.into_genesis()?;
+				let chain_spec: Box<dyn ChainSpec> = match config.chain_spec.id() {
+					"dev" => Box::new(chain_spec::development_config_with(dex)),
+					"local_testnet" => Box::new(chain_spec::local_testnet_config_with(dex)),
+					id => return Err(format!("Can't build the {} chain from a manifest", id).into()),
+				};
+				cmd.base.run(chain_spec, config.network)
+			})
+		},
 		Some(Subcommand::CheckBlock(cmd)) => {
 			construct_async_run!(|components, cli, cmd, config| {
 				Ok(cmd.run(components.client, components.import_queue))
@@ -232,6 +245,16 @@ pub fn run() -> Result<()> {
 					runner.sync_run(|config| cmd.run(&config, SUBSTRATE_REFERENCE_HARDWARE.clone())),
 			}
 		},
+		Some(Subcommand::Dex(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let partials = new_partial::<RuntimeApi, TemplateRuntimeExecutor, _>(
+					&config,
+					crate::service::parachain_build_import_queue,
+				)?;
+				cmd.run(partials.client)
+			})
+		},
 		Some(Subcommand::TryRuntime(cmd)) => {
 			if cfg!(feature = "try-runtime") {
 				let runner = cli.create_runner(cmd)?;
@@ -253,6 +276,15 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(&cli.run.normalize())?;
 			let collator_options = cli.run.collator_options();
 
+			// A `--dev` node seals its own blocks instead of collating for a relay chain
+			if cli.run.base.shared_params.is_dev() {
+				let sealing = cli.sealing;
+				return runner.run_node_until_exit(|config| async move {
+					info!("Dev node without relay chain, sealing: {}", sealing);
+					crate::service::start_dev_node(config, sealing).map_err(Into::into)
+				})
+			}
+
 			runner.run_node_until_exit(|config| async move {
 				let hwbench = if !cli.no_hardware_benchmarks {
 					config.database.path().map(|database_path| {
@@ -0,0 +1,261 @@
+//! The `dex` command, reading the DEX pools of a stopped node through the runtime API.
+
+use std::sync::Arc;
+
+use pallet_dex_rpc::DexRuntimeApi;
+use pallet_dex_runtime_api::{PoolInfo, Trade};
+use parachain_runtime::{AccountId, Balance, Block, BlockNumber, DexAssetId, Hash, Permill};
+use sc_cli::{CliConfiguration, DatabaseParams, PruningParams, Result, SharedParams};
+use serde::Serialize;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::crypto::Ss58Codec;
+use sp_runtime::{generic::BlockId, PerThing};
+
+use crate::{
+	cli::{DexAction, DexCmd, OutputFormat},
+	manifest::parse_account,
+};
+
+/// The state of a pool after a block which changed it
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryEntry {
+	number: BlockNumber,
+	hash: Hash,
+	reserves: (Balance, Balance),
+	last_trade: Option<Trade<AccountId, DexAssetId, Balance>>,
+}
+
+impl DexCmd {
+	/// Run the action against the runtime of `client`
+	pub fn run<C>(&self, client: Arc<C>) -> Result<()>
+	where
+		C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+		C::Api: DexRuntimeApi<Block, DexAssetId, Balance, AccountId>,
+	{
+		let at = self.at.unwrap_or_else(|| client.info().best_hash);
+		let api = client.runtime_api();
+		let block = BlockId::hash(at);
+
+		match &self.action {
+			DexAction::Pools => {
+				let pools = api.list_pools(&block).map_err(runtime_error)?;
+				match self.output {
+					OutputFormat::Json => print_json(&pools),
+					OutputFormat::Table => print_pools(&pools),
+				}
+			},
+			DexAction::Reserves { a, b, fee } => {
+				let (a, b) = (parse_asset(a)?, parse_asset(b)?);
+				let fee = fee.map(Permill::from_parts);
+				let reserves = api
+					.get_reserves(&block, a, b, fee)
+					.map_err(runtime_error)?
+					.ok_or_else(|| pool_not_found(a, b, fee))?;
+				match self.output {
+					OutputFormat::Json => print_json(&reserves),
+					OutputFormat::Table => {
+						println!("{:>12} {:>24}", "ASSET", "RESERVE");
+						println!("{:>12} {:>24}", asset_name(a), reserves.0);
+						println!("{:>12} {:>24}", asset_name(b), reserves.1);
+						Ok(())
+					},
+				}
+			},
+			DexAction::Positions { account } => {
+				let account = parse_account(account)?;
+				let mut positions = Vec::new();
+				for pool in api.list_pools(&block).map_err(runtime_error)? {
+					let (a, b) = pool.pair;
+					let fee = Some(pool.fee);
+					match api.position(&block, account.clone(), a, b, fee).map_err(runtime_error)? {
+						Some(position) if position.lp_balance > 0 => positions.push((pool, position)),
+						_ => (),
+					}
+				}
+				match self.output {
+					OutputFormat::Json => print_json(&positions),
+					OutputFormat::Table => {
+						println!(
+							"{:>12} {:>12} {:>8} {:>24} {:>24} {:>24}",
+							"ASSET A", "ASSET B", "FEE PPM", "LP BALANCE", "AMOUNT A", "AMOUNT B"
+						);
+						for (pool, position) in positions {
+							println!(
+								"{:>12} {:>12} {:>8} {:>24} {:>24} {:>24}",
+								asset_name(pool.pair.0),
+								asset_name(pool.pair.1),
+								pool.fee.deconstruct(),
+								position.lp_balance,
+								position.amounts.0,
+								position.amounts.1,
+							);
+						}
+						Ok(())
+					},
+				}
+			},
+			DexAction::History { a, b, from, fee } => {
+				let (a, b) = (parse_asset(a)?, parse_asset(b)?);
+				let history = history(&*client, at, *from, a, b, fee.map(Permill::from_parts))?;
+				match self.output {
+					OutputFormat::Json => print_json(&history),
+					OutputFormat::Table => {
+						println!(
+							"{:>10} {:<66} {:>24} {:>24}  LAST TRADE",
+							"BLOCK", "HASH", asset_name(a), asset_name(b)
+						);
+						for entry in history {
+							let trade = entry.last_trade.map_or_else(String::new, |trade| {
+								format!(
+									"{} swapped {} {} for {} {}",
+									trade.who.to_ss58check(),
+									trade.amount_in,
+									asset_name(trade.token_in),
+									trade.amount_out,
+									asset_name(trade.token_out),
+								)
+							});
+							println!(
+								"{:>10} {:<66} {:>24} {:>24}  {}",
+								entry.number,
+								format!("{:?}", entry.hash),
+								entry.reserves.0,
+								entry.reserves.1,
+								trade
+							);
+						}
+						Ok(())
+					},
+				}
+			},
+		}
+	}
+}
+
+/// Walk back from `at` to block `from` and keep the blocks after which the reserves or the
+/// last trade of the pool of `a` and `b` in the `fee` tier changed, oldest first
+fn history<C>(
+	client: &C,
+	at: Hash,
+	from: BlockNumber,
+	a: DexAssetId,
+	b: DexAssetId,
+	fee: Option<Permill>,
+) -> Result<Vec<HistoryEntry>>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: DexRuntimeApi<Block, DexAssetId, Balance, AccountId>,
+{
+	let mut states = Vec::new();
+	let mut hash = at;
+	loop {
+		let header = client
+			.header(BlockId::hash(hash))?
+			.ok_or_else(|| format!("Block {:?} is not in the database", hash))?;
+		if header.number < from {
+			break
+		}
+
+		let api = client.runtime_api();
+		let block = BlockId::hash(hash);
+		// Stop where the API or the pool didn't exist yet
+		let has_api = api
+			.has_api::<dyn DexRuntimeApi<Block, DexAssetId, Balance, AccountId>>(&block)
+			.map_err(runtime_error)?;
+		if !has_api {
+			break
+		}
+		let reserves = match api.get_reserves(&block, a, b, fee).map_err(runtime_error)? {
+			Some(reserves) => reserves,
+			None => break,
+		};
+		let last_trade = api.last_trade(&block, a, b, fee).map_err(runtime_error)?;
+		states.push(HistoryEntry { number: header.number, hash, reserves, last_trade });
+
+		if header.number == 0 {
+			break
+		}
+		hash = header.parent_hash;
+	}
+
+	states.reverse();
+	let mut history: Vec<HistoryEntry> = Vec::new();
+	for state in states {
+		let unchanged = history.last().map_or(false, |last| {
+			last.reserves == state.reserves && last.last_trade == state.last_trade
+		});
+		if !unchanged {
+			history.push(state);
+		}
+	}
+	Ok(history)
+}
+
+fn print_pools(pools: &[PoolInfo<DexAssetId, Balance, AccountId>]) -> Result<()> {
+	println!(
+		"{:>12} {:>12} {:>8} {:>12} {:>24} {:>24} {:>24}",
+		"ASSET A", "ASSET B", "FEE PPM", "LP TOKEN", "RESERVE A", "RESERVE B", "LP SUPPLY"
+	);
+	for pool in pools {
+		println!(
+			"{:>12} {:>12} {:>8} {:>12} {:>24} {:>24} {:>24}",
+			asset_name(pool.pair.0),
+			asset_name(pool.pair.1),
+			pool.fee.deconstruct(),
+			asset_name(pool.lp_token),
+			pool.reserves.0,
+			pool.reserves.1,
+			pool.lp_total_supply,
+		);
+	}
+	Ok(())
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+	let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+	println!("{}", json);
+	Ok(())
+}
+
+/// Parse `UNIT` as the native currency, or an asset id
+fn parse_asset(asset: &str) -> Result<DexAssetId> {
+	if asset.eq_ignore_ascii_case("UNIT") {
+		return Ok(DexAssetId::Native)
+	}
+	asset
+		.parse()
+		.map(DexAssetId::Asset)
+		.map_err(|_| format!("Invalid asset {}, expected UNIT or an asset id", asset).into())
+}
+
+fn asset_name(asset: DexAssetId) -> String {
+	match asset {
+		DexAssetId::Native => "UNIT".into(),
+		DexAssetId::Asset(id) => id.to_string(),
+	}
+}
+
+fn pool_not_found(a: DexAssetId, b: DexAssetId, fee: Option<Permill>) -> sc_cli::Error {
+	let tier = fee.map_or_else(|| "the default".into(), |fee| format!("the {} ppm", fee.deconstruct()));
+	format!("There is no pool of {} and {} in {} fee tier", asset_name(a), asset_name(b), tier).into()
+}
+
+fn runtime_error(e: sp_api::ApiError) -> sc_cli::Error {
+	format!("Error calling the DEX runtime API: {}", e).into()
+}
+
+impl CliConfiguration for DexCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+}
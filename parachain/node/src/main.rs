@@ -7,6 +7,8 @@ mod chain_spec;
 mod service;
 mod cli;
 mod command;
+mod dex;
+mod manifest;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
@@ -0,0 +1,452 @@
+//! A declarative manifest of the assets and DEX pools of a chain spec.
+//!
+//! ```toml
+//! [[assets]]
+//! id = 1
+//! name = "TOKEN_A"
+//! symbol = "TKA"
+//! decimals = 8
+//! holders = [["Alice", 1000000], ["Bob", 1000000]]
+//!
+//! [[pools]]
+//! pair = ["UNIT", "TKA"]
+//! fee_tier = 3000
+//! reserves = [2500000000, 250000]
+//! provider = "Alice"
+//! ```
+//!
+//! Accounts are either development seeds (`Alice`, `Bob//stash`) or SS58 addresses, and `UNIT`
+//! is the native currency. The same fields can be given as JSON in a `.json` file.
+//!
+//! The manifest is rejected if building the genesis from it would fail: a pool provider must
+//! keep the existential deposit after funding the native reserve and the pool account's own
+//! existential deposit, the reserves must reach the min_balance of their assets, and
+//! `sqrt(reserve_0 * reserve_1)` must exceed the `MinimumLiquidity` locked by the first deposit.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use parachain_runtime::{
+	AccountId, Balance, DexAssetId, FeeTiers, MinimumLiquidity, Permill, TokenMinimumBalance,
+	EXISTENTIAL_DEPOSIT,
+};
+use serde::Deserialize;
+use sp_core::{crypto::Ss58Codec, sr25519, Pair, U256};
+use sp_runtime::traits::IdentifyAccount;
+
+use crate::chain_spec::{endowed_accounts, DexGenesis, ENDOWMENT};
+
+/// The symbol of the native currency in pool pairs
+const NATIVE_SYMBOL: &str = "UNIT";
+
+/// The assets and pools of a chain spec
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+	#[serde(default)]
+	pub assets: Vec<AssetEntry>,
+	#[serde(default)]
+	pub pools: Vec<PoolEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AssetEntry {
+	pub id: u32,
+	pub name: String,
+	pub symbol: String,
+	pub decimals: u8,
+	/// Defaults to Alice
+	pub owner: Option<String>,
+	#[serde(default = "default_min_balance")]
+	pub min_balance: u64,
+	/// (account, balance)
+	#[serde(default)]
+	pub holders: Vec<(String, u64)>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoolEntry {
+	/// The symbols of the two assets
+	pub pair: (String, String),
+	/// In parts per million, defaults to the 0.3% tier
+	#[serde(default = "default_fee_tier")]
+	pub fee_tier: u32,
+	/// The initial reserves, in the order of `pair`
+	pub reserves: (u64, u64),
+	/// Holds the reserves and receives the LP tokens
+	pub provider: String,
+}
+
+// Amounts are read as u64 as TOML has no larger integers and serde can't read them into a u128
+fn default_min_balance() -> u64 {
+	1
+}
+
+fn default_fee_tier() -> u32 {
+	3_000
+}
+
+impl Manifest {
+	/// Read a manifest from a TOML file, or a JSON file if the extension is `.json`
+	pub fn from_file(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path)
+			.map_err(|e| format!("Error reading manifest {}: {}", path.display(), e))?;
+		let manifest = if path.extension().map_or(false, |ext| ext == "json") {
+			serde_json::from_str(&content).map_err(|e| e.to_string())
+		} else {
+			toml::from_str(&content).map_err(|e| e.to_string())
+		};
+		manifest.map_err(|e| format!("Error parsing manifest {}: {}", path.display(), e))
+	}
+
+	/// Check the manifest and turn it into the genesis of the assets and DEX pallets
+	pub fn into_genesis(self) -> Result<DexGenesis, String> {
+		let mut genesis = DexGenesis {
+			assets: Vec::new(),
+			metadata: Vec::new(),
+			accounts: Vec::new(),
+			pools: Vec::new(),
+		};
+		// The balances left to the pool providers, by asset and account
+		let mut holdings = BTreeMap::<(DexAssetId, AccountId), Balance>::new();
+		for account in endowed_accounts() {
+			holdings.insert((DexAssetId::Native, account), ENDOWMENT);
+		}
+
+		// The DEX creates the LP tokens downwards from the largest asset id
+		let max_asset_id = u32::MAX - self.pools.len() as u32;
+		let mut symbols = BTreeMap::new();
+		for asset in self.assets {
+			if asset.id > max_asset_id {
+				return Err(format!("Asset id {} is taken by the LP tokens of the pools", asset.id))
+			}
+			if genesis.assets.iter().any(|(id, ..)| *id == asset.id) {
+				return Err(format!("Asset id {} is declared twice", asset.id))
+			}
+			if asset.symbol == NATIVE_SYMBOL || symbols.contains_key(&asset.symbol) {
+				return Err(format!("Asset symbol {} is already taken", asset.symbol))
+			}
+			if asset.min_balance == 0 {
+				return Err(format!("Asset {} must have a non zero min_balance", asset.symbol))
+			}
+			let min_balance = Balance::from(asset.min_balance);
+			symbols.insert(asset.symbol.clone(), (DexAssetId::Asset(asset.id), min_balance));
+
+			let owner = parse_account(asset.owner.as_deref().unwrap_or("Alice"))?;
+			genesis.assets.push((asset.id, owner, true, min_balance));
+			genesis.metadata.push((
+				asset.id,
+				asset.name.into_bytes(),
+				asset.symbol.into_bytes(),
+				asset.decimals,
+			));
+			for (holder, balance) in asset.holders {
+				let balance = Balance::from(balance);
+				if balance < min_balance {
+					return Err(format!("{} holds less than the min_balance of asset {}", holder, asset.id))
+				}
+				let holder = parse_account(&holder)?;
+				*holdings.entry((DexAssetId::Asset(asset.id), holder.clone())).or_default() += balance;
+				genesis.accounts.push((asset.id, holder, balance));
+			}
+		}
+
+		for pool in self.pools {
+			let name = format!("{}/{}", pool.pair.0, pool.pair.1);
+			let lookup = |symbol: &String| -> Result<(DexAssetId, Balance), String> {
+				if symbol == NATIVE_SYMBOL {
+					return Ok((DexAssetId::Native, 1))
+				}
+				symbols.get(symbol).copied().ok_or_else(|| format!("Unknown asset {}", symbol))
+			};
+			let (asset_0, min_balance_0) = lookup(&pool.pair.0)?;
+			let (asset_1, min_balance_1) = lookup(&pool.pair.1)?;
+			if asset_0 == asset_1 {
+				return Err(format!("Pool {} pairs an asset with itself", name))
+			}
+			let fee = Permill::from_parts(pool.fee_tier);
+			if !FeeTiers::get().contains(&fee) {
+				return Err(format!("Pool {} has no fee tier {}", name, pool.fee_tier))
+			}
+			if genesis.pools.iter().any(|((_, a), (_, b), _, f)| {
+				*f == fee && ((*a, *b) == (asset_0, asset_1) || (*a, *b) == (asset_1, asset_0))
+			}) {
+				return Err(format!("Pool {} is declared twice", name))
+			}
+
+			let reserves = (Balance::from(pool.reserves.0), Balance::from(pool.reserves.1));
+			for (symbol, amount, min_balance) in [
+				(&pool.pair.0, reserves.0, min_balance_0),
+				(&pool.pair.1, reserves.1, min_balance_1),
+			] {
+				if amount < min_balance {
+					return Err(format!(
+						"Pool {} needs a {} reserve of at least {}",
+						name, symbol, min_balance
+					))
+				}
+			}
+			// The first deposit locks `MinimumLiquidity` and mints the rest to the provider, which
+			// must reach the min balance of the LP token
+			let liquidity = (U256::from(reserves.0) * U256::from(reserves.1)).integer_sqrt();
+			let min_liquidity =
+				Balance::from(MinimumLiquidity::get()) + Balance::from(TokenMinimumBalance::get());
+			if liquidity < U256::from(min_liquidity) {
+				return Err(format!(
+					"Pool {} reserves are too small, sqrt(reserve_0 * reserve_1) must reach {}",
+					name, min_liquidity
+				))
+			}
+
+			let provider = parse_account(&pool.provider)?;
+			for (asset, symbol, amount) in
+				[(asset_0, &pool.pair.0, reserves.0), (asset_1, &pool.pair.1, reserves.1)]
+			{
+				// The provider also funds the existential deposit of the pool account, on top of
+				// the native reserve, and must keep its own as the transfers keep it alive
+				let (needed, kept) = match asset {
+					DexAssetId::Native =>
+						(amount.saturating_add(EXISTENTIAL_DEPOSIT), EXISTENTIAL_DEPOSIT),
+					DexAssetId::Asset(_) => (amount, 0),
+				};
+				let held = holdings.entry((asset, provider.clone())).or_default();
+				*held = held.checked_sub(needed).filter(|left| *left >= kept).ok_or_else(|| {
+					format!(
+						"{} does not hold enough {} to provide {} to pool {}",
+						pool.provider, symbol, amount, name
+					)
+				})?;
+			}
+			genesis
+				.pools
+				.push(((reserves.0, asset_0), (reserves.1, asset_1), provider, fee));
+		}
+
+		Ok(genesis)
+	}
+}
+
+/// Parse an SS58 address or a development seed such as `Alice` or `Alice//stash`
+pub fn parse_account(account: &str) -> Result<AccountId, String> {
+	if let Ok(account) = AccountId::from_ss58check(account) {
+		return Ok(account)
+	}
+	let public = sr25519::Pair::from_string(&format!("//{}", account), None)
+		.map_err(|_| format!("Invalid account {}, expected an SS58 address or a seed", account))?
+		.public();
+	Ok(sp_runtime::MultiSigner::from(public).into_account())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// TKA held by Alice and Bob, TKB held by Alice only
+	const ASSETS: &str = r#"
+		[[assets]]
+		id = 1
+		name = "TOKEN_A"
+		symbol = "TKA"
+		decimals = 8
+		holders = [["Alice", 1000000], ["Bob", 1000000]]
+
+		[[assets]]
+		id = 2
+		name = "TOKEN_B"
+		symbol = "TKB"
+		decimals = 8
+		min_balance = 10
+		holders = [["Alice", 1000000]]
+	"#;
+
+	fn genesis(manifest: &str) -> Result<DexGenesis, String> {
+		toml::from_str::<Manifest>(manifest).map_err(|e| e.to_string())?.into_genesis()
+	}
+
+	fn asset(id: u32, symbol: &str, extra: &str) -> String {
+		format!(
+			"[[assets]]\nid = {}\nname = \"X\"\nsymbol = \"{}\"\ndecimals = 0\n{}\n",
+			id, symbol, extra
+		)
+	}
+
+	fn pool(pair: (&str, &str), reserves: (u64, u64), provider: &str, extra: &str) -> String {
+		format!(
+			"[[pools]]\npair = [\"{}\", \"{}\"]\nreserves = [{}, {}]\nprovider = \"{}\"\n{}\n",
+			pair.0, pair.1, reserves.0, reserves.1, provider, extra
+		)
+	}
+
+	fn assert_rejected(manifest: &str, error: &str) {
+		let result = genesis(manifest).map(|_| ());
+		assert!(
+			matches!(&result, Err(e) if e.contains(error)),
+			"expected an error containing {:?}, got {:?}",
+			error,
+			result
+		);
+	}
+
+	fn assert_pool_rejected(pair: (&str, &str), reserves: (u64, u64), provider: &str, error: &str) {
+		assert_rejected(&(ASSETS.to_string() + &pool(pair, reserves, provider, "")), error);
+	}
+
+	#[test]
+	fn builds_genesis_from_toml() {
+		let manifest =
+			ASSETS.to_string() + &pool(("UNIT", "TKA"), (2_500_000_000, 250_000), "Alice", "");
+		let genesis = genesis(&manifest).unwrap();
+		let alice = parse_account("Alice").unwrap();
+
+		assert_eq!(genesis.assets, vec![(1, alice.clone(), true, 1), (2, alice.clone(), true, 10)]);
+		assert_eq!(genesis.metadata[0], (1, b"TOKEN_A".to_vec(), b"TKA".to_vec(), 8));
+		assert_eq!(genesis.accounts.len(), 3);
+		assert_eq!(
+			genesis.pools,
+			vec![(
+				(2_500_000_000, DexAssetId::Native),
+				(250_000, DexAssetId::Asset(1)),
+				alice,
+				Permill::from_parts(3_000)
+			)]
+		);
+	}
+
+	#[test]
+	fn builds_genesis_from_json() {
+		let manifest: Manifest = serde_json::from_str(
+			r#"{
+				"assets": [
+					{ "id": 1, "name": "TOKEN_A", "symbol": "TKA", "decimals": 8, "holders": [["Bob", 5000]] }
+				],
+				"pools": [
+					{ "pair": ["TKA", "UNIT"], "fee_tier": 500, "reserves": [5000, 5000], "provider": "Bob" }
+				]
+			}"#,
+		)
+		.unwrap();
+		let genesis = manifest.into_genesis().unwrap();
+
+		assert_eq!(genesis.pools[0].0, (5_000, DexAssetId::Asset(1)));
+		assert_eq!(genesis.pools[0].3, Permill::from_parts(500));
+	}
+
+	#[test]
+	fn rejects_unknown_fields() {
+		let manifest = pool(("UNIT", "TKA"), (10_000, 10_000), "Alice", "fee = 3000");
+		assert!(toml::from_str::<Manifest>(&manifest).is_err());
+	}
+
+	#[test]
+	fn rejects_invalid_assets() {
+		assert_rejected(&(ASSETS.to_string() + &asset(1, "X", "")), "Asset id 1 is declared twice");
+		assert_rejected(&asset(3, "UNIT", ""), "Asset symbol UNIT is already taken");
+		assert_rejected(
+			&(ASSETS.to_string() + &asset(3, "TKA", "")),
+			"Asset symbol TKA is already taken",
+		);
+		let lp_id = asset(u32::MAX, "X", "") + &pool(("UNIT", "X"), (10_000, 10_000), "Alice", "");
+		assert_rejected(&lp_id, "Asset id 4294967295 is taken by the LP tokens");
+		assert_rejected(&asset(3, "X", "min_balance = 0"), "must have a non zero min_balance");
+		assert_rejected(
+			&asset(3, "X", "min_balance = 10\nholders = [[\"Bob\", 9]]"),
+			"Bob holds less than the min_balance of asset 3",
+		);
+	}
+
+	#[test]
+	fn rejects_invalid_pools() {
+		assert_pool_rejected(("UNIT", "TKC"), (10_000, 10_000), "Alice", "Unknown asset TKC");
+		assert_pool_rejected(
+			("TKA", "TKA"),
+			(10_000, 10_000),
+			"Alice",
+			"pairs an asset with itself",
+		);
+		assert_pool_rejected(
+			("TKA", "TKB"),
+			(10_000, 10_000),
+			"Alice//",
+			"Invalid account Alice//",
+		);
+
+		let fee_tier = pool(("UNIT", "TKA"), (10_000, 10_000), "Alice", "fee_tier = 42");
+		assert_rejected(&(ASSETS.to_string() + &fee_tier), "Pool UNIT/TKA has no fee tier 42");
+
+		let twice = ASSETS.to_string() +
+			&pool(("UNIT", "TKA"), (10_000, 10_000), "Alice", "") +
+			&pool(("TKA", "UNIT"), (10_000, 10_000), "Alice", "");
+		assert_rejected(&twice, "Pool TKA/UNIT is declared twice");
+	}
+
+	#[test]
+	fn rejects_reserves_below_minimum_liquidity() {
+		// sqrt(1_000 * 1_000) would all be locked, leaving no LP tokens for the provider
+		assert_pool_rejected(("TKA", "TKB"), (1_000, 1_000), "Alice", "reserves are too small");
+		let manifest = ASSETS.to_string() + &pool(("TKA", "TKB"), (1_001, 1_001), "Alice", "");
+		assert!(genesis(&manifest).is_ok());
+	}
+
+	#[test]
+	fn rejects_reserves_below_min_balance() {
+		assert_pool_rejected(
+			("TKA", "TKB"),
+			(1_000_000, 9),
+			"Alice",
+			"needs a TKB reserve of at least 10",
+		);
+		assert_pool_rejected(
+			("UNIT", "TKA"),
+			(0, 10_000),
+			"Alice",
+			"needs a UNIT reserve of at least 1",
+		);
+	}
+
+	#[test]
+	fn rejects_providers_without_the_reserves() {
+		assert_pool_rejected(
+			("TKA", "TKB"),
+			(1_000_001, 10_000),
+			"Alice",
+			"Alice does not hold enough TKA to provide 1000001 to pool TKA/TKB",
+		);
+		assert_pool_rejected(
+			("TKA", "TKB"),
+			(10_000, 10_000),
+			"Bob",
+			"Bob does not hold enough TKB",
+		);
+		// Only the development accounts are endowed with the native currency
+		assert_pool_rejected(
+			("UNIT", "TKA"),
+			(10_000, 10_000),
+			"Alice//pool",
+			"does not hold enough UNIT",
+		);
+	}
+
+	#[test]
+	fn native_provider_keeps_the_existential_deposit() {
+		// The provider funds the pool account's existential deposit and keeps its own
+		let max_reserve = (ENDOWMENT - 2 * EXISTENTIAL_DEPOSIT) as u64;
+		assert_pool_rejected(
+			("UNIT", "TKA"),
+			(max_reserve + 1, 10_000),
+			"Alice",
+			"Alice does not hold enough UNIT",
+		);
+		let manifest =
+			ASSETS.to_string() + &pool(("UNIT", "TKA"), (max_reserve, 10_000), "Alice", "");
+		assert!(genesis(&manifest).is_ok());
+	}
+
+	#[test]
+	fn parses_seeds_and_addresses() {
+		let alice = parse_account("Alice").unwrap();
+		assert_eq!(parse_account(&alice.to_ss58check()), Ok(alice.clone()));
+		assert_ne!(parse_account("Alice//stash"), Ok(alice));
+		assert!(parse_account("Alice//").is_err());
+	}
+}
@@ -7,15 +7,20 @@
 
 use std::sync::Arc;
 
-use parachain_runtime::{opaque::Block, AccountId, Balance, Index as Nonce};
+use futures::channel::mpsc::Sender;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use parachain_runtime::{opaque::Block, AccountId, Balance, DexAssetId, Hash, Index as Nonce};
 
-use sc_client_api::AuxStore;
+use sc_client_api::{AuxStore, BlockchainEvents};
+use sc_consensus_manual_seal::EngineCommand;
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 
+use crate::service::TimeTravel;
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpsee::RpcModule<()>;
 
@@ -27,6 +32,46 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Executor to drive the subscriptions.
+	pub subscription_executor: SubscriptionTaskExecutor,
+	/// Block sealing of the `--dev` node, `None` when collating.
+	pub dev: Option<DevDeps>,
+}
+
+/// Dependencies of the RPC methods driving the `--dev` node.
+pub struct DevDeps {
+	/// Channel for the `engine_*` methods to seal and finalize blocks.
+	pub command_sink: Sender<EngineCommand<Hash>>,
+	/// The time added to the timestamp of the next block.
+	pub time_travel: TimeTravel,
+}
+
+/// Clock controls of the `--dev` node.
+#[rpc(server)]
+pub trait DevApi {
+	/// Move the timestamp of the next sealed block forward by `millis`, on top of the usual
+	/// slot duration. Later blocks continue from the new time.
+	#[method(name = "dev_timeTravel")]
+	fn time_travel(&self, millis: u64) -> RpcResult<()>;
+}
+
+/// Implements [`DevApiServer`] on the clock of the `--dev` node.
+pub struct Dev {
+	time_travel: TimeTravel,
+}
+
+impl Dev {
+	/// Create new `Dev` with the given clock.
+	pub fn new(time_travel: TimeTravel) -> Self {
+		Self { time_travel }
+	}
+}
+
+impl DevApiServer for Dev {
+	fn time_travel(&self, millis: u64) -> RpcResult<()> {
+		self.time_travel.travel(millis);
+		Ok(())
+	}
 }
 
 /// Instantiate all RPC extensions.
@@ -37,6 +82,7 @@ where
 	C: ProvideRuntimeApi<Block>
 		+ HeaderBackend<Block>
 		+ AuxStore
+		+ BlockchainEvents<Block>
 		+ HeaderMetadata<Block, Error = BlockChainError>
 		+ Send
 		+ Sync
@@ -44,15 +90,25 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: pallet_dex_rpc::DexRuntimeApi<Block, DexAssetId, Balance, AccountId>,
 	P: TransactionPool + Sync + Send + 'static,
 {
+	use pallet_dex_rpc::{Dex, DexApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
+	use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcExtension::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, pool, deny_unsafe, subscription_executor, dev } = deps;
 
 	module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	module.merge(Dex::new(client, subscription_executor).into_rpc())?;
+
+	// `engine_createBlock`, `engine_finalizeBlock` and `dev_timeTravel`
+	if let Some(DevDeps { command_sink, time_travel }) = dev {
+		module.merge(ManualSeal::new(command_sink).into_rpc())?;
+		module.merge(Dev::new(time_travel).into_rpc())?;
+	}
 	Ok(module)
 }
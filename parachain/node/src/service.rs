@@ -1,7 +1,15 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 // std
-use std::{sync::Arc, time::Duration};
+use std::{
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+use futures::StreamExt;
 
 // rpc
 use jsonrpsee::RpcModule;
@@ -9,7 +17,7 @@ use jsonrpsee::RpcModule;
 use cumulus_client_cli::CollatorOptions;
 // Local Runtime Types
 use parachain_runtime::{
-	opaque::Block, AccountId, Balance, Hash, Index as Nonce, RuntimeApi,
+	opaque::Block, AccountId, Balance, DexAssetId, Hash, Index as Nonce, RuntimeApi,
 };
 
 // Cumulus Imports
@@ -20,23 +28,33 @@ use cumulus_client_service::{
 	prepare_node_config, start_collator, start_full_node, StartCollatorParams, StartFullNodeParams,
 };
 use cumulus_primitives_core::ParaId;
+use cumulus_primitives_parachain_inherent::MockValidationDataInherentDataProvider;
 use cumulus_relay_chain_inprocess_interface::build_inprocess_relay_chain;
 use cumulus_relay_chain_interface::{RelayChainError, RelayChainInterface, RelayChainResult};
 use cumulus_relay_chain_rpc_interface::RelayChainRPCInterface;
 
 // Substrate Imports
 use sc_client_api::ExecutorProvider;
+use sc_consensus_manual_seal::{
+	consensus::{aura::AuraConsensusDataProvider, timestamp::SlotTimestampProvider},
+	EngineCommand, ManualSealParams,
+};
 use sc_executor::NativeElseWasmExecutor;
 use sc_network::NetworkService;
 use sc_service::{Configuration, PartialComponents, Role, TFullBackend, TFullClient, TaskManager};
+use sc_transaction_pool_api::TransactionPool;
 use sc_telemetry::{Telemetry, TelemetryHandle, TelemetryWorker, TelemetryWorkerHandle};
 use sp_api::ConstructRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_inherents::{InherentData, InherentDataProvider, InherentIdentifier};
 use sp_keystore::SyncCryptoStorePtr;
 use sp_runtime::traits::BlakeTwo256;
 use substrate_prometheus_endpoint::Registry;
 
 use polkadot_service::CollatorPair;
 
+use crate::cli::Sealing;
+
 /// Native executor instance.
 pub struct TemplateRuntimeExecutor;
 
@@ -220,7 +238,8 @@ where
 		+ sp_block_builder::BlockBuilder<Block>
 		+ cumulus_primitives_core::CollectCollationInfo<Block>
 		+ pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>
-		+ substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
+		+ substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>
+		+ pallet_dex_rpc::DexRuntimeApi<Block, DexAssetId, Balance, AccountId>,
 	sc_client_api::StateBackendFor<TFullBackend<Block>, Block>: sp_api::StateBackend<BlakeTwo256>,
 	Executor: sc_executor::NativeExecutionDispatch + 'static,
 	RB: Fn(
@@ -308,11 +327,13 @@ where
 		let client = client.clone();
 		let transaction_pool = transaction_pool.clone();
 
-		Box::new(move |deny_unsafe, _| {
+		Box::new(move |deny_unsafe, subscription_executor| {
 			let deps = crate::rpc::FullDeps {
 				client: client.clone(),
 				pool: transaction_pool.clone(),
 				deny_unsafe,
+				subscription_executor,
+				dev: None,
 			};
 
 			crate::rpc::create_full(deps).map_err(Into::into)
@@ -533,3 +554,216 @@ pub async fn start_parachain_node(
 	)
 	.await
 }
+
+/// Build the import queue of the standalone `--dev` node, which imports its own blocks only.
+#[allow(clippy::type_complexity)]
+pub fn dev_build_import_queue(
+	client: Arc<TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<TemplateRuntimeExecutor>>>,
+	config: &Configuration,
+	_: Option<TelemetryHandle>,
+	task_manager: &TaskManager,
+) -> Result<
+	sc_consensus::DefaultImportQueue<
+		Block,
+		TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<TemplateRuntimeExecutor>>,
+	>,
+	sc_service::Error,
+> {
+	Ok(sc_consensus_manual_seal::import_queue(
+		Box::new(client),
+		&task_manager.spawn_essential_handle(),
+		config.prometheus_registry(),
+	))
+}
+
+/// Time to add to the timestamp of the next block sealed by the `--dev` node, on top of the
+/// usual slot duration.
+#[derive(Clone, Default)]
+pub struct TimeTravel(Arc<AtomicU64>);
+
+impl TimeTravel {
+	/// Move the timestamp of the next block forward by `millis`.
+	pub fn travel(&self, millis: u64) {
+		self.0.fetch_add(millis, Ordering::SeqCst);
+	}
+
+	fn take(&self) -> u64 {
+		self.0.swap(0, Ordering::SeqCst)
+	}
+}
+
+/// Timestamp of the next block sealed by the `--dev` node: one Aura slot after the best block,
+/// plus the time travelled since the last block.
+struct DevTimestampProvider {
+	slot_timestamp: SlotTimestampProvider,
+	travelled: u64,
+}
+
+#[async_trait::async_trait]
+impl InherentDataProvider for DevTimestampProvider {
+	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), sp_inherents::Error> {
+		self.slot_timestamp.provide_inherent_data(inherent_data)?;
+		if self.travelled > 0 {
+			let timestamp: u64 = inherent_data
+				.get_data(&sp_timestamp::INHERENT_IDENTIFIER)?
+				.unwrap_or_default();
+			inherent_data
+				.replace_data(sp_timestamp::INHERENT_IDENTIFIER, &(timestamp + self.travelled));
+		}
+		Ok(())
+	}
+
+	async fn try_handle_error(
+		&self,
+		identifier: &InherentIdentifier,
+		error: &[u8],
+	) -> Option<Result<(), sp_inherents::Error>> {
+		self.slot_timestamp.try_handle_error(identifier, error).await
+	}
+}
+
+/// Start a standalone node which seals its own blocks, without a relay chain.
+///
+/// The parachain inherents are mocked, and every block moves the timestamp at least one Aura
+/// slot forward so the runtime's slot checks pass. Besides the blocks sealed according to
+/// `sealing`, blocks can be sealed and finalized over the `engine_*` RPC methods.
+pub fn start_dev_node(
+	config: Configuration,
+	sealing: Sealing,
+) -> sc_service::error::Result<TaskManager> {
+	let params = new_partial::<RuntimeApi, TemplateRuntimeExecutor, _>(
+		&config,
+		dev_build_import_queue,
+	)?;
+	let (mut telemetry, _) = params.other;
+
+	let client = params.client.clone();
+	let backend = params.backend.clone();
+	let mut task_manager = params.task_manager;
+	let transaction_pool = params.transaction_pool.clone();
+	let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+	let (network, system_rpc_tx, start_network) =
+		sc_service::build_network(sc_service::BuildNetworkParams {
+			config: &config,
+			client: client.clone(),
+			transaction_pool: transaction_pool.clone(),
+			spawn_handle: task_manager.spawn_handle(),
+			import_queue: params.import_queue,
+			block_announce_validator_builder: None,
+			warp_sync: None,
+		})?;
+
+	// Commands sent over the `engine_*` RPC methods
+	let (command_sink, rpc_commands) = futures::channel::mpsc::channel(1024);
+	let time_travel = TimeTravel::default();
+
+	let rpc_builder = {
+		let client = client.clone();
+		let transaction_pool = transaction_pool.clone();
+		let time_travel = time_travel.clone();
+
+		Box::new(move |deny_unsafe, subscription_executor| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: transaction_pool.clone(),
+				deny_unsafe,
+				subscription_executor,
+				dev: Some(crate::rpc::DevDeps {
+					command_sink: command_sink.clone(),
+					time_travel: time_travel.clone(),
+				}),
+			};
+
+			crate::rpc::create_full(deps).map_err(Into::into)
+		})
+	};
+
+	let prometheus_registry = config.prometheus_registry().cloned();
+
+	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+		rpc_builder,
+		client: client.clone(),
+		transaction_pool: transaction_pool.clone(),
+		task_manager: &mut task_manager,
+		config,
+		keystore: params.keystore_container.sync_keystore(),
+		backend,
+		network,
+		system_rpc_tx,
+		telemetry: telemetry.as_mut(),
+	})?;
+
+	let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry.as_ref(),
+		telemetry.as_ref().map(|t| t.handle()),
+	);
+
+	let create_inherent_data_providers = {
+		let client = client.clone();
+		move |parent, ()| {
+			let client = client.clone();
+			let travelled = time_travel.take();
+			async move {
+				let current_para_block = client
+					.number(parent)?
+					.ok_or_else(|| format!("Unknown parent block {:?}", parent))?;
+				let timestamp = DevTimestampProvider {
+					slot_timestamp: SlotTimestampProvider::new_aura(client)?,
+					travelled,
+				};
+				let parachain_inherent = MockValidationDataInherentDataProvider {
+					current_para_block,
+					relay_offset: 1000,
+					relay_blocks_per_para_block: 2,
+					xcm_config: Default::default(),
+					raw_downward_messages: Vec::new(),
+					raw_horizontal_messages: Vec::new(),
+				};
+				Ok((timestamp, parachain_inherent))
+			}
+		}
+	};
+
+	// Blocks sealed on their own according to the sealing mode, finalized right away
+	let seal_and_finalize = |create_empty| EngineCommand::SealNewBlock {
+		create_empty,
+		finalize: true,
+		parent_hash: None,
+		sender: None,
+	};
+	let sealing_commands = match sealing {
+		Sealing::Instant => transaction_pool
+			.import_notification_stream()
+			.map(move |_| seal_and_finalize(false))
+			.boxed(),
+		Sealing::Manual => futures::stream::pending().boxed(),
+		Sealing::Interval(millis) => futures::stream::unfold((), move |()| async move {
+			futures_timer::Delay::new(Duration::from_millis(millis)).await;
+			Some((seal_and_finalize(true), ()))
+		})
+		.boxed(),
+	};
+
+	let authorship_future = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+		block_import: client.clone(),
+		env: proposer_factory,
+		client: client.clone(),
+		pool: transaction_pool,
+		commands_stream: futures::stream::select(rpc_commands, sealing_commands),
+		select_chain,
+		consensus_data_provider: Some(Box::new(AuraConsensusDataProvider::new(client))),
+		create_inherent_data_providers,
+	});
+
+	task_manager
+		.spawn_essential_handle()
+		.spawn_blocking("manual-seal", None, authorship_future);
+
+	start_network.start_network();
+
+	Ok(task_manager)
+}
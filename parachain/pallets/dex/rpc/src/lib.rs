@@ -0,0 +1,279 @@
+//! RPC interface for the DEX pallet, on top of the `DexApi` runtime API.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use futures::{future, FutureExt, StreamExt};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+	PendingSubscription,
+};
+use sc_client_api::BlockchainEvents;
+use sc_rpc::SubscriptionTaskExecutor;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT, FixedPointNumber, FixedU128, Permill};
+
+pub use pallet_dex_runtime_api::DexApi as DexRuntimeApi;
+use pallet_dex_runtime_api::{PoolInfo, Position, Trade};
+
+/// Whether a quote is for an exact input or an exact output amount
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuoteKind {
+	/// The output of swapping exactly `amount` of the first asset of the path
+	ExactIn,
+	/// The input needed to receive exactly `amount` of the last asset of the path
+	ExactOut,
+}
+
+/// The state of a pool after a block which changed it
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolUpdate<BlockHash, AssetId, Balance, AccountId> {
+	/// The block the update is for
+	pub block: BlockHash,
+	/// The reserves, as (reserve of `a`, reserve of `b`)
+	pub reserves: (Balance, Balance),
+	/// The price of `a` in `b`, absent while the pool is empty
+	pub spot_price: Option<FixedU128>,
+	/// The last swap through the pool
+	pub last_trade: Option<Trade<AccountId, AssetId, Balance>>,
+}
+
+/// Pools are looked up in the `fee` tier, or the `DefaultFee` tier if `None`.
+#[rpc(client, server)]
+pub trait DexApi<BlockHash, AssetId, Balance, AccountId> {
+	/// The reserves of the pool of `a` and `b`, as (reserve of `a`, reserve of `b`)
+	#[method(name = "dex_getReserves")]
+	fn get_reserves(
+		&self,
+		a: AssetId,
+		b: AssetId,
+		fee: Option<Permill>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<(Balance, Balance)>>;
+
+	/// Quote a swap of `amount` along `path`, for an exact input unless `kind` says otherwise
+	#[method(name = "dex_quote")]
+	fn quote(
+		&self,
+		path: Vec<AssetId>,
+		amount: Balance,
+		kind: Option<QuoteKind>,
+		fee: Option<Permill>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<Balance>>;
+
+	/// All liquidity pools with their reserves
+	#[method(name = "dex_listPools")]
+	fn list_pools(&self, at: Option<BlockHash>) -> RpcResult<Vec<PoolInfo<AssetId, Balance, AccountId>>>;
+
+	/// The liquidity `account` provides to the pool of `a` and `b`
+	#[method(name = "dex_getPosition")]
+	fn get_position(
+		&self,
+		account: AccountId,
+		a: AssetId,
+		b: AssetId,
+		fee: Option<Permill>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<Position<Balance>>>;
+
+	/// Push the state of the pool of `a` and `b` now and after every imported block which
+	/// changed it
+	#[subscription(
+		name = "dex_subscribePool" => "dex_pool",
+		unsubscribe = "dex_unsubscribePool",
+		item = PoolUpdate<BlockHash, AssetId, Balance, AccountId>,
+	)]
+	fn subscribe_pool(&self, a: AssetId, b: AssetId, fee: Option<Permill>);
+}
+
+/// Provides RPC methods to query the DEX pools.
+pub struct Dex<C, Block> {
+	client: Arc<C>,
+	executor: SubscriptionTaskExecutor,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> Dex<C, Block> {
+	/// Create new `Dex` with the given reference to the client and the executor running
+	/// subscriptions.
+	pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+		Self { client, executor, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// There is no pool for the given assets.
+	PoolNotFound,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::PoolNotFound => 2,
+		}
+	}
+}
+
+/// Map a failed runtime API call to an RPC error
+fn runtime_error(message: &str, e: impl ToString) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(Error::RuntimeError.into(), message, Some(e.to_string())))
+		.into()
+}
+
+/// Read the state of the pool of `a` and `b` at `block`, `None` if there is no such pool
+fn pool_update<C, Block, AssetId, Balance, AccountId>(
+	client: &C,
+	block: <Block as BlockT>::Hash,
+	a: AssetId,
+	b: AssetId,
+	fee: Option<Permill>,
+) -> Result<
+	Option<PoolUpdate<<Block as BlockT>::Hash, AssetId, Balance, AccountId>>,
+	sp_api::ApiError,
+>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: DexRuntimeApi<Block, AssetId, Balance, AccountId>,
+	AssetId: Codec + Clone,
+	Balance: Codec + Copy + Into<u128>,
+	AccountId: Codec,
+{
+	let api = client.runtime_api();
+	let at = BlockId::hash(block);
+
+	let reserves = match api.get_reserves(&at, a.clone(), b.clone(), fee)? {
+		Some(reserves) => reserves,
+		None => return Ok(None),
+	};
+	let spot_price = FixedU128::checked_from_rational(reserves.1.into(), reserves.0.into());
+	let last_trade = api.last_trade(&at, a, b, fee)?;
+	Ok(Some(PoolUpdate { block, reserves, spot_price, last_trade }))
+}
+
+impl<C, Block, AssetId, Balance, AccountId>
+	DexApiServer<<Block as BlockT>::Hash, AssetId, Balance, AccountId> for Dex<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>
+		+ HeaderBackend<Block>
+		+ BlockchainEvents<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	C::Api: DexRuntimeApi<Block, AssetId, Balance, AccountId>,
+	AssetId: Codec + Clone + Send + Sync + 'static,
+	Balance: Codec + Copy + Into<u128> + Send + Sync + 'static,
+	AccountId: Codec + Send + Sync + 'static,
+{
+	fn get_reserves(
+		&self,
+		a: AssetId,
+		b: AssetId,
+		fee: Option<Permill>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<(Balance, Balance)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_reserves(&at, a, b, fee).map_err(|e| runtime_error("Unable to query reserves.", e))
+	}
+
+	fn quote(
+		&self,
+		path: Vec<AssetId>,
+		amount: Balance,
+		kind: Option<QuoteKind>,
+		fee: Option<Permill>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		match kind.unwrap_or(QuoteKind::ExactIn) {
+			QuoteKind::ExactIn => api.quote_exact_in(&at, path, amount, fee),
+			QuoteKind::ExactOut => api.quote_exact_out(&at, path, amount, fee),
+		}
+		.map_err(|e| runtime_error("Unable to quote swap.", e))
+	}
+
+	fn list_pools(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<PoolInfo<AssetId, Balance, AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.list_pools(&at).map_err(|e| runtime_error("Unable to list pools.", e))
+	}
+
+	fn get_position(
+		&self,
+		account: AccountId,
+		a: AssetId,
+		b: AssetId,
+		fee: Option<Permill>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<Position<Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.position(&at, account, a, b, fee).map_err(|e| runtime_error("Unable to query position.", e))
+	}
+
+	fn subscribe_pool(&self, pending: PendingSubscription, a: AssetId, b: AssetId, fee: Option<Permill>) {
+		let best = self.client.info().best_hash;
+
+		let api = self.client.runtime_api();
+		let keys = match api.pool_storage_keys(&BlockId::hash(best), a.clone(), b.clone(), fee) {
+			Ok(keys) if keys.is_empty() => {
+				let _ = pending.reject(CallError::Custom(ErrorObject::owned(
+					Error::PoolNotFound.into(),
+					"No pool for the given assets.",
+					None::<()>,
+				)));
+				return
+			},
+			Ok(keys) => keys.into_iter().map(StorageKey).collect::<Vec<_>>(),
+			Err(e) => {
+				let _ = pending.reject(runtime_error("Unable to query pool storage keys.", e));
+				return
+			},
+		};
+
+		let changes = match self.client.storage_changes_notification_stream(Some(&keys), None) {
+			Ok(changes) => changes,
+			Err(e) => {
+				let _ = pending.reject(runtime_error("Unable to subscribe to storage changes.", e));
+				return
+			},
+		};
+
+		// Send the current state first, then the state after every block which changed the pool
+		let client = self.client.clone();
+		let stream = futures::stream::iter(Some(best))
+			.chain(changes.map(|notification| notification.block))
+			.filter_map(move |block| {
+				future::ready(pool_update(&*client, block, a.clone(), b.clone(), fee).ok().flatten())
+			});
+
+		let fut = async move {
+			if let Some(mut sink) = pending.accept() {
+				sink.pipe_from_stream(stream).await;
+			}
+		};
+		self.executor.spawn("dex-rpc-subscription", Some("rpc"), fut.boxed());
+	}
+}
@@ -0,0 +1,96 @@
+//! Runtime API definition for the DEX pallet, so clients don't have to decode raw storage.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{Permill, RuntimeDebug};
+use sp_std::vec::Vec;
+
+/// A liquidity pool and its current reserves
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PoolInfo<AssetId, Balance, AccountId> {
+	/// The pooled assets, in pair order
+	pub pair: (AssetId, AssetId),
+	/// The swap fee tier of the pool
+	pub fee: Permill,
+	/// The LP token of the pool
+	pub lp_token: AssetId,
+	/// The account holding the reserves
+	pub account: AccountId,
+	/// The reserves, in pair order
+	pub reserves: (Balance, Balance),
+	/// The total supply of the LP token
+	pub lp_total_supply: Balance,
+}
+
+/// The liquidity an account provides to a pool
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Position<Balance> {
+	/// The LP tokens held by the account
+	pub lp_balance: Balance,
+	/// The reserves the LP tokens can be redeemed for, in the order they were queried
+	pub amounts: (Balance, Balance),
+	/// The total supply of the LP token
+	pub lp_total_supply: Balance,
+}
+
+/// A swap through a pool
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Trade<AccountId, AssetId, Balance> {
+	/// The account which swapped
+	pub who: AccountId,
+	/// The asset paid into the pool
+	pub token_in: AssetId,
+	/// The amount paid into the pool
+	pub amount_in: Balance,
+	/// The asset paid out of the pool
+	pub token_out: AssetId,
+	/// The amount paid out of the pool
+	pub amount_out: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Queries of the DEX pools. Pairs are looked up in the `fee` tier, or the `DefaultFee`
+	/// tier if `None`. `list_pools` returns the pools of every tier.
+	pub trait DexApi<AssetId, Balance, AccountId> where
+		AssetId: Codec,
+		Balance: Codec,
+		AccountId: Codec,
+	{
+		/// The reserves of the pool of `a` and `b`, as (reserve of `a`, reserve of `b`)
+		fn get_reserves(a: AssetId, b: AssetId, fee: Option<Permill>) -> Option<(Balance, Balance)>;
+
+		/// The output of swapping exactly `amount` of `path[0]` along `path`, through the `fee`
+		/// tier pool of every hop, as `swap_exact_in_along_path` executes it in that tier
+		fn quote_exact_in(path: Vec<AssetId>, amount: Balance, fee: Option<Permill>) -> Option<Balance>;
+
+		/// The input of `path[0]` needed to receive exactly `amount` of the last asset of `path`,
+		/// through the `fee` tier pool of every hop, as `swap_exact_out_along_path` executes it
+		fn quote_exact_out(path: Vec<AssetId>, amount: Balance, fee: Option<Permill>) -> Option<Balance>;
+
+		/// All liquidity pools
+		fn list_pools() -> Vec<PoolInfo<AssetId, Balance, AccountId>>;
+
+		/// The LP token of the pool of `a` and `b`
+		fn lp_token_of(a: AssetId, b: AssetId, fee: Option<Permill>) -> Option<AssetId>;
+
+		/// The liquidity `account` provides to the pool of `a` and `b`
+		fn position(account: AccountId, a: AssetId, b: AssetId, fee: Option<Permill>) -> Option<Position<Balance>>;
+
+		/// The last swap through the pool of `a` and `b`
+		fn last_trade(a: AssetId, b: AssetId, fee: Option<Permill>) -> Option<Trade<AccountId, AssetId, Balance>>;
+
+		/// The storage keys written on every reserve change of the pool of `a` and `b`, empty
+		/// if there is no such pool
+		fn pool_storage_keys(a: AssetId, b: AssetId, fee: Option<Permill>) -> Vec<Vec<u8>>;
+	}
+}
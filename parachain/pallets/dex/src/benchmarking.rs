@@ -0,0 +1,196 @@
+//! Benchmarking setup for pallet-dex
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Dex;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::{
+	sp_std::vec::Vec,
+	traits::{
+		tokens::fungibles::{Create, Inspect, Mutate},
+		Currency, EnsureOrigin, Get,
+	},
+	BoundedVec,
+};
+use frame_system::RawOrigin;
+use sp_runtime::SaturatedConversion;
+
+/// The balance every asset is minted with for the caller
+fn funds<T: Config>() -> BalanceOf<T> {
+	1_000_000_000_000_000_000u128.saturated_into()
+}
+
+/// The amount of each asset deposited into a pool
+fn liquidity<T: Config>() -> BalanceOf<T> {
+	1_000_000_000_000u128.saturated_into()
+}
+
+/// The first asset id created by the benchmarks, clear of the assets of the genesis config
+const FIRST_ASSET_ID: u32 = 1_000;
+
+/// Create `n` assets funded for `who`, returned as pool assets
+fn create_assets<T: Config>(who: &T::AccountId, n: u32) -> Vec<MultiAssetIdOf<T>> {
+	T::NativeCurrency::make_free_balance_be(who, funds::<T>());
+	(FIRST_ASSET_ID..FIRST_ASSET_ID + n)
+		.map(|id| {
+			let id: T::AssetId = id.into();
+			T::Tokens::create(id, who.clone(), true, 1u32.into()).unwrap();
+			T::Tokens::mint_into(id, who, funds::<T>()).unwrap();
+			MultiAssetId::Asset(id)
+		})
+		.collect()
+}
+
+/// The pair of a pool benchmark, the native currency and an asset if `n` is 1, which also moves
+/// balances and the pool account's existential deposit, and two assets otherwise
+fn create_pair<T: Config>(who: &T::AccountId, n: u32) -> (MultiAssetIdOf<T>, MultiAssetIdOf<T>) {
+	let assets = create_assets::<T>(who, 2);
+	if n == 1 {
+		(MultiAssetId::Native, assets[0])
+	} else {
+		(assets[0], assets[1])
+	}
+}
+
+/// Create the `DefaultFee` pool of two assets and deposit `liquidity` of both
+fn create_pool<T: Config>(who: &T::AccountId, token_0: MultiAssetIdOf<T>, token_1: MultiAssetIdOf<T>) {
+	let origin = RawOrigin::Signed(who.clone());
+	Dex::<T>::create_pool(origin.clone().into(), token_0, token_1, None).unwrap();
+	Dex::<T>::add_liquidity(
+		origin.into(),
+		liquidity::<T>(),
+		token_0,
+		liquidity::<T>(),
+		token_1,
+		None,
+		Default::default(),
+		Default::default(),
+	)
+	.unwrap();
+}
+
+/// Create the pool of `create_pair` with the protocol fee on and fee growth since the last
+/// liquidity event, so the next deposit or withdrawal mints the protocol fee
+fn create_pool_with_protocol_fee<T: Config>(
+	who: &T::AccountId,
+	n: u32,
+) -> (MultiAssetIdOf<T>, MultiAssetIdOf<T>) {
+	let (token_0, token_1) = create_pair::<T>(who, n);
+	create_pool::<T>(who, token_0, token_1);
+	Dex::<T>::set_protocol_fee(T::ProtocolFeeOrigin::successful_origin(), token_0, token_1, None, true)
+		.unwrap();
+	Dex::<T>::swap_exact_tokens_for_tokens(
+		RawOrigin::Signed(who.clone()).into(),
+		token_0,
+		token_1,
+		None,
+		liquidity::<T>() / 10u32.into(),
+		Default::default(),
+	)
+	.unwrap();
+	(token_0, token_1)
+}
+
+/// Create a swap path through `h` pools, routed through the native currency as its second asset
+/// so the first two hops move balances
+fn create_path<T: Config>(who: &T::AccountId, h: u32) -> BoundedVec<MultiAssetIdOf<T>, T::MaxHops> {
+	let mut path = create_assets::<T>(who, h);
+	path.insert(1, MultiAssetId::Native);
+	for hop in path.windows(2) {
+		create_pool::<T>(who, hop[0], hop[1]);
+	}
+	path.try_into().unwrap()
+}
+
+benchmarks! {
+	create_pool {
+		let n in 0 .. 1;
+		let caller: T::AccountId = whitelisted_caller();
+		let (token_0, token_1) = create_pair::<T>(&caller, n);
+	}: _(RawOrigin::Signed(caller), token_0, token_1, None)
+	verify {
+		assert!(Dex::<T>::get_pool(token_0, token_1, None).is_ok());
+	}
+
+	add_liquidity {
+		let n in 0 .. 1;
+		let caller: T::AccountId = whitelisted_caller();
+		let (token_0, token_1) = create_pool_with_protocol_fee::<T>(&caller, n);
+		let amount = liquidity::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), amount, token_0, amount, token_1, None, Default::default(), Default::default())
+	verify {
+		let pool = Dex::<T>::get_pool(token_0, token_1, None).unwrap();
+		assert!(T::Tokens::balance(pool.id, &T::ProtocolFeeReceiver::get()) > Default::default());
+	}
+
+	remove_liquidity {
+		let n in 0 .. 1;
+		let caller: T::AccountId = whitelisted_caller();
+		let (token_0, token_1) = create_pool_with_protocol_fee::<T>(&caller, n);
+		let pool = Dex::<T>::get_pool(token_0, token_1, None).unwrap();
+		let lp_amount = T::Tokens::balance(pool.id, &caller) / 2u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), token_0, token_1, None, lp_amount, Default::default(), Default::default())
+	verify {
+		assert!(T::Tokens::balance(pool.id, &T::ProtocolFeeReceiver::get()) > Default::default());
+	}
+
+	swap_exact_tokens_for_tokens {
+		let n in 0 .. 1;
+		let caller: T::AccountId = whitelisted_caller();
+		let (token_0, token_1) = create_pair::<T>(&caller, n);
+		create_pool::<T>(&caller, token_0, token_1);
+		let amount_in = liquidity::<T>() / 10u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), token_0, token_1, None, amount_in, Default::default())
+	verify {
+		assert!(Dex::<T>::last_trade(token_0, token_1, None).is_some());
+	}
+
+	swap_tokens_for_exact_tokens {
+		let n in 0 .. 1;
+		let caller: T::AccountId = whitelisted_caller();
+		let (token_0, token_1) = create_pair::<T>(&caller, n);
+		create_pool::<T>(&caller, token_0, token_1);
+		let amount_out = liquidity::<T>() / 10u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), token_0, token_1, None, amount_out, funds::<T>())
+	verify {
+		assert!(Dex::<T>::last_trade(token_0, token_1, None).is_some());
+	}
+
+	swap_exact_in_along_path {
+		let h in 1 .. T::MaxHops::get() - 1;
+		let caller: T::AccountId = whitelisted_caller();
+		let path = create_path::<T>(&caller, h);
+		let (first, last) = (path[0], path[path.len() - 1]);
+		let (first_before, last_before) = (Dex::<T>::balance(first, &caller), Dex::<T>::balance(last, &caller));
+		let amount_in = liquidity::<T>() / 10u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), path, None, amount_in, Default::default())
+	verify {
+		assert_eq!(Dex::<T>::balance(first, &caller), first_before - amount_in);
+		assert!(Dex::<T>::balance(last, &caller) > last_before);
+	}
+
+	swap_exact_out_along_path {
+		let h in 1 .. T::MaxHops::get() - 1;
+		let caller: T::AccountId = whitelisted_caller();
+		let path = create_path::<T>(&caller, h);
+		let last = path[path.len() - 1];
+		let last_before = Dex::<T>::balance(last, &caller);
+		let amount_out = liquidity::<T>() / 10u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), path, None, amount_out, funds::<T>())
+	verify {
+		assert_eq!(Dex::<T>::balance(last, &caller), last_before + amount_out);
+	}
+
+	set_protocol_fee {
+		let caller: T::AccountId = whitelisted_caller();
+		let (token_0, token_1) = create_pair::<T>(&caller, 0);
+		create_pool::<T>(&caller, token_0, token_1);
+		let origin = T::ProtocolFeeOrigin::successful_origin();
+	}: _<T::Origin>(origin, token_0, token_1, None, true)
+	verify {
+		assert!(Dex::<T>::get_pool(token_0, token_1, None).unwrap().protocol_fee_on);
+	}
+
+	impl_benchmark_test_suite!(Dex, crate::mock::new_test_ext(), crate::mock::Test);
+}
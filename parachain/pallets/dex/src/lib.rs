@@ -14,31 +14,45 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod weights;
+pub use weights::WeightInfo;
+
+pub mod traits;
+pub use traits::Swap;
+
 #[frame_support::pallet]
 pub mod pallet {
-	use sp_runtime::{traits::AtLeast32BitUnsigned, traits::Bounded};
-	use frame_support::traits::{Currency, ReservableCurrency};
+	use sp_runtime::{traits::AtLeast32BitUnsigned, traits::Bounded, traits::CheckedAdd, traits::CheckedSub, FixedPointNumber, FixedU128, Permill, SaturatedConversion};
+	use sp_core::U256;
+	use frame_support::traits::{Currency, ExistenceRequirement, ReservableCurrency, Time};
 	use frame_support::traits::tokens::fungibles::{Mutate, InspectMetadata, Inspect, Create};
 	use frame_support::traits::tokens::fungibles::metadata::Mutate as MutateMetadata;
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 	use frame_support::PalletId;
+	use frame_support::sp_std::{vec, vec::Vec};
 	use codec::HasCompact;
+	#[cfg(feature = "std")]
+	use serde::{Deserialize, Serialize};
 	use sp_runtime::traits::AccountIdConversion;
+	use crate::WeightInfo;
 
 	type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 
 	/// Needed to inspect an token`
-	type AssetIdOf<T> =
+	pub type AssetIdOf<T> =
 		<<T as Config>::Tokens as Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
 
 	/// Needed to insepct a token
-	type BalanceOf<T> =
+	pub type BalanceOf<T> =
 		<<T as Config>::Tokens as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 		
 	type NativeBalanceOf<T> =
 		<<T as Config>::NativeCurrency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+	/// Either the native currency or a token, as used in pool pairs
+	pub type MultiAssetIdOf<T> = MultiAssetId<AssetIdOf<T>>;
+
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
@@ -48,6 +62,11 @@ pub mod pallet {
 		type LpTokenMinimumBalance: Get<
 			<Self::Tokens as Inspect<<Self as frame_system::Config>::AccountId>>::Balance>;
 
+		/// LP tokens permanently locked in the pallet account on the first deposit of a pool.
+		/// Must be at least `LpTokenMinimumBalance` so the locked balance can't be reaped.
+		type MinimumLiquidity: Get<
+			<Self::Tokens as Inspect<<Self as frame_system::Config>::AccountId>>::Balance>;
+
 		type LpTokenDecimals: Get<u8>;
 
 		type Tokens: Create<Self::AccountId>
@@ -73,30 +92,123 @@ pub mod pallet {
 
 		type PalletId: Get<PalletId>;
 
-		type NativeTokenId: Get<Self::AssetId>;
+		/// The swap fee used when no fee tier is given
+		type DefaultFee: Get<Permill>;
+
+		/// The fee tiers a pool can be created with, e.g. 0.05% for stable pairs and 1% for
+		/// volatile pairs. Must contain `DefaultFee`.
+		type FeeTiers: Get<Vec<Permill>>;
+
+		/// The account receiving the protocol's share of swap fees as LP tokens
+		type ProtocolFeeReceiver: Get<Self::AccountId>;
+
+		/// The protocol receives `1 / ProtocolFeeDenominator` of a pool's fee growth, e.g. 6
+		/// to match Uniswap V2
+		type ProtocolFeeDenominator: Get<u32>;
+
+		/// The origin allowed to switch the protocol fee on and off for a pool
+		type ProtocolFeeOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The maximum number of assets in a multi-hop swap path
+		type MaxHops: Get<u32>;
 
 		type NativeCurrency: ReservableCurrency<Self::AccountId>;
 
+		/// The amount of native currency reserved from the creator of a pool
+		type PoolCreationDeposit: Get<NativeBalanceOf<Self>>;
+
+		/// The symbol of the native currency, used for the metadata of its LP tokens
+		type NativeSymbol: Get<Vec<u8>>;
+
+		/// The time source of the price accumulators, i.e. `pallet_timestamp`
+		type Time: Time;
+
+		/// The number of price observations kept per pool for TWAP queries, must be non-zero
+		type MaxObservations: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+
 		fn exists(id: Self::AssetId) -> bool;
 	}
 
+	/// An asset which can be pooled: the native currency or one of the `Tokens` assets
+	#[derive(
+		Clone, Copy, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, RuntimeDebug, MaxEncodedLen, TypeInfo,
+	)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum MultiAssetId<AssetId> {
+		Native,
+		Asset(AssetId),
+	}
+
 	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
 	#[scale_info(skip_type_params(T))]
 	pub struct LiquidityPool<T: Config> {
 		pub id: AssetIdOf<T>,
-		pub pair: (AssetIdOf<T>, AssetIdOf<T>),
+		pub pair: (MultiAssetIdOf<T>, MultiAssetIdOf<T>),
 		pub account: AccountIdOf<T>,
+		/// Share of every swap input kept by the pool for its liquidity providers
+		pub fee: Permill,
+		/// The reserve of `pair.0`, only changed by deposits, withdrawals and swaps so tokens
+		/// sent straight to `account` don't move the price
+		pub reserve_0: BalanceOf<T>,
+		/// The reserve of `pair.1`, see `reserve_0`
+		pub reserve_1: BalanceOf<T>,
+		/// Whether a share of the fee growth is minted to `ProtocolFeeReceiver`
+		pub protocol_fee_on: bool,
+		/// `sqrt(reserve_0 * reserve_1)` as of the last liquidity event, zero while the
+		/// protocol fee is off (Uniswap's `kLast`)
+		pub root_k_last: BalanceOf<T>,
+		/// Sum of the price of `pair.0` in `pair.1` (as `FixedU128` inner value) weighted by
+		/// the time it was valid for, wrapping on overflow
+		pub price0_cumulative: U256,
+		/// Sum of the price of `pair.1` in `pair.0`, see `price0_cumulative`
+		pub price1_cumulative: U256,
+		/// The moment the accumulators were last updated
+		pub last_timestamp: u64,
+	}
+
+	/// A snapshot of the price accumulators of a pool, used to compute TWAPs
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	pub struct Observation {
+		pub timestamp: u64,
+		pub price0_cumulative: U256,
+		pub price1_cumulative: U256,
 	}
 
 	impl<T: Config> LiquidityPool<T> {
-		pub fn new_liquidity_pool(pair: (AssetIdOf<T>, AssetIdOf<T>)) -> Result<Self, DispatchError> {
+		/// Create the pool of `pair` in a fee tier and its LP token.
+		///
+		/// For a pair with the native currency the creator funds the existential deposit of the
+		/// pool account on top of the reserves, so paying out the whole reserve never reaps it.
+		pub fn new_liquidity_pool(
+			pair: (MultiAssetIdOf<T>, MultiAssetIdOf<T>),
+			fee: Permill,
+			creator: &AccountIdOf<T>,
+		) -> Result<Self, DispatchError> {
 			let lp_token_id = Self::create_liquidity_pool_token(pair)?;
 			let account = T::PalletId::get().into_sub_account_truncating(lp_token_id);
-			let pool = Self { id: lp_token_id, pair, account };
+			if pair.0 == MultiAssetId::Native || pair.1 == MultiAssetId::Native {
+				Pallet::<T>::transfer(MultiAssetId::Native, creator, &account, T::NativeCurrency::minimum_balance())?;
+			}
+			let pool = Self {
+				id: lp_token_id,
+				pair,
+				account,
+				fee,
+				reserve_0: Default::default(),
+				reserve_1: Default::default(),
+				protocol_fee_on: false,
+				root_k_last: Default::default(),
+				price0_cumulative: Default::default(),
+				price1_cumulative: Default::default(),
+				last_timestamp: Pallet::<T>::now(),
+			};
 			Ok(pool)
 		}
 
-		fn create_liquidity_pool_token(pair: (AssetIdOf<T>, AssetIdOf<T>)) -> Result<AssetIdOf<T>, DispatchError> {
+		fn create_liquidity_pool_token(pair: (MultiAssetIdOf<T>, MultiAssetIdOf<T>)) -> Result<AssetIdOf<T>, DispatchError> {
 			// Check if we have a liquidity pool token id
 			let lp_token_id = <GetLpTokenId<T>>::get().unwrap_or_else(|| AssetIdOf::<T>::max_value());
 
@@ -109,9 +221,9 @@ pub mod pallet {
 			T::Tokens::create(lp_token_id, dex_id.clone(), true, T::LpTokenMinimumBalance::get())?;
 
 			// Set asset metadata based on existing assets
-			let mut asset_0 = T::Tokens::symbol(&pair.0);
+			let mut asset_0 = Pallet::<T>::symbol(pair.0);
 
-			let asset_1 = T::Tokens::symbol(&pair.1);
+			let asset_1 = Pallet::<T>::symbol(pair.1);
 
 			asset_0.extend(asset_1);
 
@@ -121,29 +233,323 @@ pub mod pallet {
 			Ok(lp_token_id)
 		}
 
+		/// Deposit liquidity into the pool and mint LP tokens to the sender.
+		///
+		/// `desired` and `min` are given in the same order as `pair`. The first deposit mints
+		/// `sqrt(a0 * a1) - MinimumLiquidity`, locking the minimum in the pallet account. For an
+		/// existing pool only the optimal amounts for the current reserve ratio are pulled from
+		/// the sender, and the minted amount is `min(a0 * S / r0, a1 * S / r1)`.
+		/// Returns (amt_0, amt_1, minted).
 		pub fn add_liquidity(
-			&self,
-			amounts: (BalanceOf<T>, BalanceOf<T>),
+			&mut self,
+			desired: (BalanceOf<T>, BalanceOf<T>),
+			min: (BalanceOf<T>, BalanceOf<T>),
 			sender: &AccountIdOf<T>,
-		) -> DispatchResult {
+		) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
+			let reserves = self.reserves();
+			self.update_cumulative_prices()?;
+			self.mint_protocol_fee(reserves)?;
 			let issuance = T::Tokens::total_issuance(self.id);
-			if issuance == <BalanceOf<T>>::default() {
-				T::Tokens::mint_into(self.id, sender, amounts.0)?;
-				T::Tokens::teleport(self.pair.0, sender, &self.account, amounts.0)?;
-				T::Tokens::teleport(self.pair.1, sender, &self.account, amounts.1)?;
+
+			let (amounts, to_mint) = if issuance == <BalanceOf<T>>::default() {
+				// Lock the minimum liquidity forever, so the share price can't be inflated by the
+				// first depositor
+				let minimum = T::MinimumLiquidity::get();
+				let to_mint = Self::sqrt_mul(desired.0, desired.1)?
+					.checked_sub(&minimum)
+					.filter(|to_mint| *to_mint > <BalanceOf<T>>::default())
+					.ok_or(Error::<T>::InsufficientLiquidity)?;
+				let dex_id: T::AccountId = T::PalletId::get().into_account_truncating();
+				T::Tokens::mint_into(self.id, &dex_id, minimum)?;
+				(desired, to_mint)
 			} else {
-				let balances = (
-					T::Tokens::balance(self.pair.0, &self.account),
-					T::Tokens::balance(self.pair.1, &self.account),
+				ensure!(
+					reserves.0 > <BalanceOf<T>>::default() && reserves.1 > <BalanceOf<T>>::default(),
+					Error::<T>::InsufficientLiquidity
 				);
-				let amount_1 = amounts.0 * (balances.1 / balances.0);
-				let to_mint = amounts.0 * (issuance / balances.0);
-				T::Tokens::mint_into(self.id, sender, to_mint)?;
-				T::Tokens::teleport(self.pair.0, &sender, &self.account, amounts.0)?;
-				T::Tokens::teleport(self.pair.1, &sender, &self.account, amounts.1)?;
+
+				// Only pull the counterpart amount which matches the current reserve ratio
+				let amount_1_optimal = Self::mul_div(desired.0, reserves.1, reserves.0)?;
+				let amounts = if amount_1_optimal <= desired.1 {
+					ensure!(amount_1_optimal >= min.1, Error::<T>::InsufficientOutputAmount);
+					(desired.0, amount_1_optimal)
+				} else {
+					let amount_0_optimal = Self::mul_div(desired.1, reserves.0, reserves.1)?;
+					ensure!(amount_0_optimal <= desired.0, Error::<T>::InvalidAmount);
+					ensure!(amount_0_optimal >= min.0, Error::<T>::InsufficientOutputAmount);
+					(amount_0_optimal, desired.1)
+				};
+
+				let to_mint = Self::mul_div(amounts.0, issuance, reserves.0)?
+					.min(Self::mul_div(amounts.1, issuance, reserves.1)?);
+				(amounts, to_mint)
+			};
+			ensure!(to_mint > <BalanceOf<T>>::default(), Error::<T>::InsufficientLiquidity);
+
+			Pallet::<T>::transfer(self.pair.0, sender, &self.account, amounts.0)?;
+			Pallet::<T>::transfer(self.pair.1, sender, &self.account, amounts.1)?;
+			T::Tokens::mint_into(self.id, sender, to_mint)?;
+			self.reserve_0 = reserves.0.checked_add(&amounts.0).ok_or(Error::<T>::Overflow)?;
+			self.reserve_1 = reserves.1.checked_add(&amounts.1).ok_or(Error::<T>::Overflow)?;
+			self.update_root_k_last()?;
+			Ok((amounts.0, amounts.1, to_mint))
+		}
+
+		/// Burn `lp_amount` LP tokens from the sender and return the pro rata share of both
+		/// reserves, in the same order as `pair`
+		pub fn remove_liquidity(
+			&mut self,
+			lp_amount: BalanceOf<T>,
+			sender: &AccountIdOf<T>,
+		) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+			let reserves = self.reserves();
+			self.update_cumulative_prices()?;
+			self.mint_protocol_fee(reserves)?;
+			let issuance = T::Tokens::total_issuance(self.id);
+			ensure!(issuance > <BalanceOf<T>>::default(), Error::<T>::InsufficientLiquidity);
+
+			// amount = reserve * lp_amount / issuance
+			let amount_0 = Self::mul_div(reserves.0, lp_amount, issuance)?;
+			let amount_1 = Self::mul_div(reserves.1, lp_amount, issuance)?;
+			ensure!(
+				amount_0 > <BalanceOf<T>>::default() && amount_1 > <BalanceOf<T>>::default(),
+				Error::<T>::InsufficientLiquidity
+			);
+
+			T::Tokens::burn_from(self.id, sender, lp_amount)?;
+			Pallet::<T>::transfer(self.pair.0, &self.account, sender, amount_0)?;
+			Pallet::<T>::transfer(self.pair.1, &self.account, sender, amount_1)?;
+			self.reserve_0 = reserves.0 - amount_0;
+			self.reserve_1 = reserves.1 - amount_1;
+			self.update_root_k_last()?;
+			Ok((amount_0, amount_1))
+		}
+
+		/// Switch the protocol fee on or off. Fees accrued while the switch was off are not
+		/// charged retroactively.
+		pub fn set_protocol_fee(&mut self, enabled: bool) -> DispatchResult {
+			self.protocol_fee_on = enabled;
+			self.root_k_last = Default::default();
+			self.update_root_k_last()
+		}
+
+		/// Mint the protocol's share of the fee growth since the last liquidity event as LP
+		/// tokens to `ProtocolFeeReceiver`. Must be called before the issuance is read for a
+		/// deposit or withdrawal.
+		///
+		/// With `n = ProtocolFeeDenominator` this mints
+		/// `S * (sqrt(k) - sqrt(k_last)) / ((n - 1) * sqrt(k) + sqrt(k_last))`.
+		fn mint_protocol_fee(&mut self, reserves: (BalanceOf<T>, BalanceOf<T>)) -> DispatchResult {
+			if !self.protocol_fee_on {
+				self.root_k_last = Default::default();
+				return Ok(())
+			}
+			if self.root_k_last == <BalanceOf<T>>::default() {
+				return Ok(())
+			}
+
+			let root_k = Self::sqrt_mul(reserves.0, reserves.1)?;
+			if root_k <= self.root_k_last {
+				return Ok(())
+			}
+
+			let issuance = T::Tokens::total_issuance(self.id);
+			let numerator = U256::from(issuance.saturated_into::<u128>()) *
+				U256::from((root_k - self.root_k_last).saturated_into::<u128>());
+			let denominator = U256::from(root_k.saturated_into::<u128>()) *
+				U256::from(T::ProtocolFeeDenominator::get().saturating_sub(1)) +
+				U256::from(self.root_k_last.saturated_into::<u128>());
+			let liquidity = numerator / denominator;
+			ensure!(liquidity <= U256::from(u128::MAX), Error::<T>::Overflow);
+			let liquidity = BalanceOf::<T>::try_from(liquidity.low_u128())
+				.map_err(|_| Error::<T>::Overflow)?;
+
+			if liquidity > <BalanceOf<T>>::default() {
+				T::Tokens::mint_into(self.id, &T::ProtocolFeeReceiver::get(), liquidity)?;
+			}
+			Ok(())
+		}
+
+		/// Record `sqrt(k)` of the current reserves while the protocol fee is on
+		fn update_root_k_last(&mut self) -> DispatchResult {
+			if self.protocol_fee_on {
+				let reserves = self.reserves();
+				self.root_k_last = Self::sqrt_mul(reserves.0, reserves.1)?;
+			}
+			Ok(())
+		}
+
+		/// Accumulate the prices of the reserves before they change, weighted by the time since
+		/// the last update, and record an observation. Must be called before every reserve
+		/// change.
+		pub fn update_cumulative_prices(&mut self) -> DispatchResult {
+			let reserves = self.reserves();
+			let now = Pallet::<T>::now();
+			let elapsed = now.saturating_sub(self.last_timestamp);
+			if elapsed == 0 {
+				return Ok(())
+			}
+
+			let (price0_cumulative, price1_cumulative) = self.cumulative_prices_at(reserves, now);
+			self.price0_cumulative = price0_cumulative;
+			self.price1_cumulative = price1_cumulative;
+			self.last_timestamp = now;
+
+			// Keep the latest `MaxObservations`, dropping the oldest one when full
+			<Observations<T>>::try_mutate(self.pair, self.fee, |observations| {
+				if observations.len() as u32 >= T::MaxObservations::get() && !observations.is_empty() {
+					observations.remove(0);
+				}
+				observations
+					.try_push(Observation { timestamp: now, price0_cumulative, price1_cumulative })
+					.map_err(|_| Error::<T>::Overflow.into())
+			})
+		}
+
+		/// The accumulators as they would be at `now` with `reserves` unchanged since the last
+		/// update
+		pub fn cumulative_prices_at(&self, reserves: (BalanceOf<T>, BalanceOf<T>), now: u64) -> (U256, U256) {
+			let elapsed = U256::from(now.saturating_sub(self.last_timestamp));
+			if reserves.0 == <BalanceOf<T>>::default() || reserves.1 == <BalanceOf<T>>::default() {
+				return (self.price0_cumulative, self.price1_cumulative)
+			}
+
+			let (reserve_0, reserve_1) = (
+				U256::from(reserves.0.saturated_into::<u128>()),
+				U256::from(reserves.1.saturated_into::<u128>()),
+			);
+			let accuracy = U256::from(FixedU128::accuracy());
+			let price0 = reserve_1 * accuracy / reserve_0;
+			let price1 = reserve_0 * accuracy / reserve_1;
+			(
+				self.price0_cumulative.overflowing_add(price0.saturating_mul(elapsed)).0,
+				self.price1_cumulative.overflowing_add(price1.saturating_mul(elapsed)).0,
+			)
+		}
+
+		/// Get the reserves of the pool, in the same order as `pair`. These are the amounts
+		/// deposited and swapped into the pool, not the balances of the pool account, which also
+		/// holds its existential deposit and whatever was sent to it directly.
+		pub fn reserves(&self) -> (BalanceOf<T>, BalanceOf<T>) {
+			(self.reserve_0, self.reserve_1)
+		}
+
+		/// Get the reserves of the pool as (reserve_in, reserve_out) for a given input token
+		pub fn reserves_for(&self, token_in: MultiAssetIdOf<T>) -> (BalanceOf<T>, BalanceOf<T>) {
+			let reserves = self.reserves();
+			if token_in == self.pair.0 {
+				reserves
+			} else {
+				(reserves.1, reserves.0)
+			}
+		}
+
+		/// Given an input amount, return the output amount which keeps x * y = k after the pool
+		/// fee is taken from the input
+		pub fn get_amount_out(
+			&self,
+			amount_in: BalanceOf<T>,
+			reserve_in: BalanceOf<T>,
+			reserve_out: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			ensure!(amount_in > <BalanceOf<T>>::default(), Error::<T>::AmountZero);
+			ensure!(
+				reserve_in > <BalanceOf<T>>::default() && reserve_out > <BalanceOf<T>>::default(),
+				Error::<T>::InsufficientLiquidity
+			);
+
+			// amount_out = reserve_out * amount_in_with_fee / (reserve_in + amount_in_with_fee)
+			let amount_in_with_fee = self.fee.left_from_one().mul_floor(amount_in);
+			let denominator =
+				reserve_in.checked_add(&amount_in_with_fee).ok_or(Error::<T>::Overflow)?;
+			Self::mul_div(reserve_out, amount_in_with_fee, denominator)
+		}
+
+		/// Given an output amount, return the input amount required to keep x * y = k after the
+		/// pool fee is taken from the input
+		pub fn get_amount_in(
+			&self,
+			amount_out: BalanceOf<T>,
+			reserve_in: BalanceOf<T>,
+			reserve_out: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			ensure!(amount_out > <BalanceOf<T>>::default(), Error::<T>::AmountZero);
+			ensure!(
+				reserve_in > <BalanceOf<T>>::default() && amount_out < reserve_out,
+				Error::<T>::InsufficientLiquidity
+			);
+
+			// amount_in = reserve_in * amount_out / ((reserve_out - amount_out) * (1 - fee)), rounded up
+			let amount_in_with_fee = Self::mul_div(reserve_in, amount_out, reserve_out - amount_out)?;
+			let amount_in = self
+				.fee
+				.left_from_one()
+				.saturating_reciprocal_mul_ceil(amount_in_with_fee)
+				.checked_add(&1u32.into())
+				.ok_or(Error::<T>::Overflow)?;
+			Ok(amount_in)
+		}
+
+		/// Calculate `sqrt(a * b)` with U256 intermediate math so the product can't overflow
+		fn sqrt_mul(a: BalanceOf<T>, b: BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+			let result = (U256::from(a.saturated_into::<u128>()) *
+				U256::from(b.saturated_into::<u128>()))
+			.integer_sqrt();
+			BalanceOf::<T>::try_from(result.low_u128()).map_err(|_| Error::<T>::Overflow.into())
+		}
+
+		/// Calculate `a * b / c` with U256 intermediate math so the product can't overflow
+		fn mul_div(
+			a: BalanceOf<T>,
+			b: BalanceOf<T>,
+			c: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			ensure!(c > <BalanceOf<T>>::default(), Error::<T>::InsufficientLiquidity);
+			let result = U256::from(a.saturated_into::<u128>()) *
+				U256::from(b.saturated_into::<u128>()) /
+				U256::from(c.saturated_into::<u128>());
+			ensure!(result <= U256::from(u128::MAX), Error::<T>::Overflow);
+			BalanceOf::<T>::try_from(result.low_u128()).map_err(|_| Error::<T>::Overflow.into())
+		}
+
+		/// Record a swap of `amount_in` of `token_in` for `amount_out` of the other token of the
+		/// pair in the reserves
+		fn apply_swap(
+			&mut self,
+			token_in: MultiAssetIdOf<T>,
+			amount_in: BalanceOf<T>,
+			amount_out: BalanceOf<T>,
+		) -> DispatchResult {
+			let (reserve_in, reserve_out) = self.reserves_for(token_in);
+			let reserve_in = reserve_in.checked_add(&amount_in).ok_or(Error::<T>::Overflow)?;
+			let reserve_out = reserve_out.checked_sub(&amount_out).ok_or(Error::<T>::InsufficientLiquidity)?;
+			if token_in == self.pair.0 {
+				self.reserve_0 = reserve_in;
+				self.reserve_1 = reserve_out;
+			} else {
+				self.reserve_0 = reserve_out;
+				self.reserve_1 = reserve_in;
 			}
 			Ok(())
 		}
+
+		/// Move `amount_in` of `token_in` from the sender into the pool and pay out `amount_out`
+		/// of the other token of the pair
+		pub fn swap(
+			&mut self,
+			token_in: MultiAssetIdOf<T>,
+			amount_in: BalanceOf<T>,
+			amount_out: BalanceOf<T>,
+			sender: &AccountIdOf<T>,
+		) -> DispatchResult {
+			self.update_cumulative_prices()?;
+			let token_out = if token_in == self.pair.0 { self.pair.1 } else { self.pair.0 };
+			Pallet::<T>::transfer(token_in, sender, &self.account, amount_in)?;
+			Pallet::<T>::transfer(token_out, &self.account, sender, amount_out)?;
+			self.apply_swap(token_in, amount_in, amount_out)?;
+			<LastTrades<T>>::insert(self.pair, self.fee, (sender.clone(), token_in, amount_in, token_out, amount_out));
+			Ok(())
+		}
 	}
 
 	/// Used to make sure pools of two tokens can only exist once
@@ -154,9 +560,9 @@ pub mod pallet {
 	/// Can use inspect ()
 	impl<T: Config> Pair<T> {
 		pub fn new_pair(
-			token_0: AssetIdOf<T>,
-			token_1: AssetIdOf<T>,
-		) -> (AssetIdOf<T>, AssetIdOf<T>) {
+			token_0: MultiAssetIdOf<T>,
+			token_1: MultiAssetIdOf<T>,
+		) -> (MultiAssetIdOf<T>, MultiAssetIdOf<T>) {
 			if token_1 < token_0 {
 				(token_1, token_0)
 			} else {
@@ -173,16 +579,56 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type GetLpTokenId<T: Config> = StorageValue<_, AssetIdOf<T>>;
 
+	/// Liquidity pools keyed by (pair, fee tier), so a pair can have a pool per fee tier
+	#[pallet::storage]
+	pub(super) type LiquidityPools<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		(MultiAssetIdOf<T>, MultiAssetIdOf<T>),
+		Twox64Concat,
+		Permill,
+		LiquidityPool<T>,
+	>;
+
+	/// The last swap through every pool (who, token_in, amt_in, token_out, amt_out)
 	#[pallet::storage]
-	pub(super) type LiquidityPools<T: Config> = StorageMap<_, Twox64Concat, (AssetIdOf<T>, AssetIdOf<T>), LiquidityPool<T>>;
+	pub(super) type LastTrades<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		(MultiAssetIdOf<T>, MultiAssetIdOf<T>),
+		Twox64Concat,
+		Permill,
+		(AccountIdOf<T>, MultiAssetIdOf<T>, BalanceOf<T>, MultiAssetIdOf<T>, BalanceOf<T>),
+	>;
+
+	/// Price accumulator snapshots of every pool, oldest first
+	#[pallet::storage]
+	pub(super) type Observations<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		(MultiAssetIdOf<T>, MultiAssetIdOf<T>),
+		Twox64Concat,
+		Permill,
+		BoundedVec<Observation, T::MaxObservations>,
+		ValueQuery,
+	>;
 	
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// For when an LP is created (token_0, token_1)
-		LiquidityPoolCreated(AssetIdOf<T>, AssetIdOf<T>),
+		/// For when an LP is created (token_0, token_1, fee, lp_token, pool_account)
+		LiquidityPoolCreated(MultiAssetIdOf<T>, MultiAssetIdOf<T>, Permill, AssetIdOf<T>, AccountIdOf<T>),
 		/// For when liquidity is added to pre-existing pool (token_0, amt_0, token_1, amt_1)
-		LiquidityAdded(AssetIdOf<T>,  BalanceOf<T>, AssetIdOf<T>, BalanceOf<T>),
+		LiquidityAdded(MultiAssetIdOf<T>,  BalanceOf<T>, MultiAssetIdOf<T>, BalanceOf<T>),
+		/// For when liquidity is removed from a pool (token_0, amt_0, token_1, amt_1)
+		LiquidityRemoved(MultiAssetIdOf<T>, BalanceOf<T>, MultiAssetIdOf<T>, BalanceOf<T>),
+		/// For when the protocol fee of a pool is switched (token_0, token_1, fee, enabled)
+		ProtocolFeeSet(MultiAssetIdOf<T>, MultiAssetIdOf<T>, Permill, bool),
+		/// For when tokens are swapped through a pool (who, token_in, amt_in, token_out, amt_out)
+		Swapped(AccountIdOf<T>, MultiAssetIdOf<T>, BalanceOf<T>, MultiAssetIdOf<T>, BalanceOf<T>),
+		/// For when tokens are swapped along a path of pools
+		/// (who, [(token_in, amt_in, token_out, amt_out)] for every hop)
+		SwappedAlongPath(AccountIdOf<T>, Vec<(MultiAssetIdOf<T>, BalanceOf<T>, MultiAssetIdOf<T>, BalanceOf<T>)>),
 	}
 
 	// Errors inform users that something went wrong.
@@ -200,17 +646,69 @@ pub mod pallet {
 		InsufficientBalance,
 		/// Sent a non-existent token
 		NonExistentToken,
+		/// There is no liquidity pool for the given pair
+		PoolNotFound,
+		/// A liquidity pool for the given pair and fee tier already exists
+		PoolAlreadyExists,
+		/// The pool does not hold enough reserves for the trade
+		InsufficientLiquidity,
+		/// The swap would pay out less than `amount_out_min`
+		InsufficientOutputAmount,
+		/// The swap would cost more than `amount_in_max`
+		ExcessiveInputAmount,
+		/// An arithmetic operation overflowed
+		Overflow,
+		/// The fee is not one of the configured fee tiers
+		InvalidFeeTier,
+		/// A swap path needs at least two assets and can't go through a pool twice
+		InvalidPath,
+		/// There is no price observation old enough for the requested TWAP window
+		ObservationNotFound,
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(10_000)]
+		/// Create the pool of two tokens in a fee tier, reserving `PoolCreationDeposit` from the
+		/// creator. A pool of the native currency also takes its existential deposit from the
+		/// creator.
+		#[pallet::weight(T::WeightInfo::create_pool(Self::native_pools(&[*token_0, *token_1])))]
+		pub fn create_pool(
+			origin: OriginFor<T>,
+			token_0: MultiAssetIdOf<T>,
+			token_1: MultiAssetIdOf<T>,
+			fee_tier: Option<Permill>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(token_0 != token_1, Error::<T>::IdenticalTokens);
+			ensure!(Self::exists(token_0) && Self::exists(token_1), Error::<T>::NonExistentToken);
+			let fee = Self::fee_tier(fee_tier)?;
+
+			let pair = Pair::<T>::new_pair(token_0, token_1);
+			ensure!(!<LiquidityPools<T>>::contains_key(pair, fee), Error::<T>::PoolAlreadyExists);
+
+			T::NativeCurrency::reserve(&sender, T::PoolCreationDeposit::get())?;
+
+			let pool = <LiquidityPool<T>>::new_liquidity_pool(pair, fee, &sender)?;
+			<LiquidityPools<T>>::insert(pair, fee, &pool);
+			Self::deposit_event(Event::LiquidityPoolCreated(pair.0, pair.1, fee, pool.id, pool.account));
+			Ok(())
+		}
+
+		/// Add liquidity to an existing pool of two tokens.
+		///
+		/// Only the amounts matching the current reserve ratio are deposited, failing if either
+		/// is below `min_0` / `min_1`
+		#[pallet::weight(T::WeightInfo::add_liquidity(Self::native_pools(&[*token_0, *token_1])))]
 		pub fn add_liquidity(
 			origin: OriginFor<T>,
 			amount_0: BalanceOf<T>,
-			token_0: AssetIdOf<T>,
+			token_0: MultiAssetIdOf<T>,
 			amount_1: BalanceOf<T>,
-			token_1: AssetIdOf<T>,
+			token_1: MultiAssetIdOf<T>,
+			fee_tier: Option<Permill>,
+			min_0: BalanceOf<T>,
+			min_1: BalanceOf<T>,
 		) -> DispatchResult {
 
 			// Make sure extrinsic is signed
@@ -220,8 +718,7 @@ pub mod pallet {
 			ensure!(token_0 != token_1, Error::<T>::IdenticalTokens); // Make sure we don't input the same token twice
 			ensure!(amount_0 > <BalanceOf<T>>::default(), Error::<T>::AmountZero); // Make sure we don't input zero amount
 			ensure!(amount_1 > <BalanceOf<T>>::default(), Error::<T>::AmountZero); // Make sure we don't input zero amount
-			ensure!(amount_0 != <BalanceOf<T>>::default() && amount_1 != <BalanceOf<T>>::default(), Error::<T>::InvalidAmount); // Check if either amount valid
-			ensure!(T::exists(token_0) && T::exists(token_1), Error::<T>::NonExistentToken); // Ensure token exists
+			ensure!(min_0 <= amount_0 && min_1 <= amount_1, Error::<T>::InvalidAmount); // Make sure the bounds can be met
 			
 			// Ensure sender has sufficient balance
 			ensure!(Self::balance(token_0, &sender) >= amount_0
@@ -229,25 +726,20 @@ pub mod pallet {
 				Error::<T>::InsufficientBalance
 			); 
 
-			// Create pair from supplied tokens
-			let pair = Pair::<T>::new_pair(token_0, token_1);
+			// Get liquidity pool, pools are created with `create_pool`
+			let mut pool = Self::get_pool(token_0, token_1, fee_tier)?;
+
+			// Add liquidity, amounts are passed and returned in pair order
+			let sorted = token_0 == pool.pair.0;
+			let (desired, min) = if sorted {
+				((amount_0, amount_1), (min_0, min_1))
+			} else {
+				((amount_1, amount_0), (min_1, min_0))
+			};
+			let (added_0, added_1, _) = pool.add_liquidity(desired, min, &sender)?;
+			<LiquidityPools<T>>::insert(pool.pair, pool.fee, &pool);
+			let (amount_0, amount_1) = if sorted { (added_0, added_1) } else { (added_1, added_0) };
 
-			// Get/create liquidity pool
-			let lp_key = (pair.0, pair.1);
-
-			let pool = match <LiquidityPools<T>>::get(lp_key) {
-				Some(pool) => Result::<LiquidityPool<T>, DispatchError>::Ok(pool),
-				None => {
-					// Create new pool, save and emit event
-					let pool = <LiquidityPool<T>>::new_liquidity_pool(lp_key)?;
-					<LiquidityPools<T>>::set(lp_key, Some(pool.clone()));
-					Self::deposit_event(Event::LiquidityPoolCreated(pair.0, pair.1));
-					Ok(pool)
-				},
-			}?;
-
-			// Add liquidity
-			pool.add_liquidity((amount_0, amount_1), &sender)?;
 			Self::deposit_event(Event::LiquidityAdded(
 				token_0,
 				amount_0,
@@ -256,17 +748,410 @@ pub mod pallet {
 			));
 			Ok(())
 		}
+
+		/// Burn LP tokens and withdraw the proportional share of both reserves, failing if
+		/// either amount is below `min_0` / `min_1`
+		#[pallet::weight(T::WeightInfo::remove_liquidity(Self::native_pools(&[*token_0, *token_1])))]
+		pub fn remove_liquidity(
+			origin: OriginFor<T>,
+			token_0: MultiAssetIdOf<T>,
+			token_1: MultiAssetIdOf<T>,
+			fee_tier: Option<Permill>,
+			lp_amount: BalanceOf<T>,
+			min_0: BalanceOf<T>,
+			min_1: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(token_0 != token_1, Error::<T>::IdenticalTokens);
+			ensure!(lp_amount > <BalanceOf<T>>::default(), Error::<T>::AmountZero);
+
+			let mut pool = Self::get_pool(token_0, token_1, fee_tier)?;
+			ensure!(T::Tokens::balance(pool.id, &sender) >= lp_amount, Error::<T>::InsufficientBalance);
+
+			// Withdrawn amounts follow the pair ordering, map them back to the caller's order
+			let withdrawn = pool.remove_liquidity(lp_amount, &sender)?;
+			<LiquidityPools<T>>::insert(pool.pair, pool.fee, &pool);
+			let (amount_0, amount_1) =
+				if token_0 == pool.pair.0 { withdrawn } else { (withdrawn.1, withdrawn.0) };
+			ensure!(amount_0 >= min_0 && amount_1 >= min_1, Error::<T>::InsufficientOutputAmount);
+
+			Self::deposit_event(Event::LiquidityRemoved(token_0, amount_0, token_1, amount_1));
+			Ok(())
+		}
+
+		/// Swap an exact amount of `path[0]` for as much of the last asset of `path` as the
+		/// route gives, hopping through the pool of every consecutive pair in `fee_tier`,
+		/// `DefaultFee` if `None`
+		#[pallet::weight(T::WeightInfo::swap_exact_in_along_path(Self::hops(path)))]
+		pub fn swap_exact_in_along_path(
+			origin: OriginFor<T>,
+			path: BoundedVec<MultiAssetIdOf<T>, T::MaxHops>,
+			fee_tier: Option<Permill>,
+			amount_in: BalanceOf<T>,
+			amount_out_min: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(amount_in > <BalanceOf<T>>::default(), Error::<T>::AmountZero);
+			ensure!(path.len() >= 2, Error::<T>::InvalidPath);
+			ensure!(Self::balance(path[0], &sender) >= amount_in, Error::<T>::InsufficientBalance);
+
+			let amounts = Self::get_amounts_out(amount_in, &path, fee_tier)?;
+			ensure!(amounts[amounts.len() - 1] >= amount_out_min, Error::<T>::InsufficientOutputAmount);
+
+			Self::do_swap_along_path(&sender, &path, fee_tier, &amounts)
+		}
+
+		/// Swap as little of `path[0]` as needed to receive exactly `amount_out` of the last
+		/// asset of `path`, hopping through the pool of every consecutive pair in `fee_tier`,
+		/// `DefaultFee` if `None`
+		#[pallet::weight(T::WeightInfo::swap_exact_out_along_path(Self::hops(path)))]
+		pub fn swap_exact_out_along_path(
+			origin: OriginFor<T>,
+			path: BoundedVec<MultiAssetIdOf<T>, T::MaxHops>,
+			fee_tier: Option<Permill>,
+			amount_out: BalanceOf<T>,
+			amount_in_max: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(amount_out > <BalanceOf<T>>::default(), Error::<T>::AmountZero);
+			ensure!(path.len() >= 2, Error::<T>::InvalidPath);
+
+			let amounts = Self::get_amounts_in(amount_out, &path, fee_tier)?;
+			ensure!(amounts[0] <= amount_in_max, Error::<T>::ExcessiveInputAmount);
+			ensure!(Self::balance(path[0], &sender) >= amounts[0], Error::<T>::InsufficientBalance);
+
+			Self::do_swap_along_path(&sender, &path, fee_tier, &amounts)
+		}
+
+		/// Switch the protocol fee of a pool on or off
+		#[pallet::weight(T::WeightInfo::set_protocol_fee())]
+		pub fn set_protocol_fee(
+			origin: OriginFor<T>,
+			token_0: MultiAssetIdOf<T>,
+			token_1: MultiAssetIdOf<T>,
+			fee_tier: Option<Permill>,
+			enabled: bool,
+		) -> DispatchResult {
+			T::ProtocolFeeOrigin::ensure_origin(origin)?;
+
+			let mut pool = Self::get_pool(token_0, token_1, fee_tier)?;
+			pool.set_protocol_fee(enabled)?;
+			<LiquidityPools<T>>::insert(pool.pair, pool.fee, &pool);
+
+			Self::deposit_event(Event::ProtocolFeeSet(pool.pair.0, pool.pair.1, pool.fee, enabled));
+			Ok(())
+		}
+
+		/// Swap an exact amount of `token_in` for as much `token_out` as the pool gives,
+		/// failing if that is less than `amount_out_min`
+		#[pallet::weight(T::WeightInfo::swap_exact_tokens_for_tokens(Self::native_pools(&[*token_in, *token_out])))]
+		pub fn swap_exact_tokens_for_tokens(
+			origin: OriginFor<T>,
+			token_in: MultiAssetIdOf<T>,
+			token_out: MultiAssetIdOf<T>,
+			fee_tier: Option<Permill>,
+			amount_in: BalanceOf<T>,
+			amount_out_min: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(token_in != token_out, Error::<T>::IdenticalTokens);
+			ensure!(amount_in > <BalanceOf<T>>::default(), Error::<T>::AmountZero);
+			ensure!(Self::balance(token_in, &sender) >= amount_in, Error::<T>::InsufficientBalance);
+
+			let mut pool = Self::get_pool(token_in, token_out, fee_tier)?;
+			let (reserve_in, reserve_out) = pool.reserves_for(token_in);
+			let amount_out = pool.get_amount_out(amount_in, reserve_in, reserve_out)?;
+			ensure!(amount_out >= amount_out_min, Error::<T>::InsufficientOutputAmount);
+
+			pool.swap(token_in, amount_in, amount_out, &sender)?;
+			<LiquidityPools<T>>::insert(pool.pair, pool.fee, &pool);
+			Self::deposit_event(Event::Swapped(sender, token_in, amount_in, token_out, amount_out));
+			Ok(())
+		}
+
+		/// Swap as little `token_in` as needed to receive exactly `amount_out` of `token_out`,
+		/// failing if that costs more than `amount_in_max`
+		#[pallet::weight(T::WeightInfo::swap_tokens_for_exact_tokens(Self::native_pools(&[*token_in, *token_out])))]
+		pub fn swap_tokens_for_exact_tokens(
+			origin: OriginFor<T>,
+			token_in: MultiAssetIdOf<T>,
+			token_out: MultiAssetIdOf<T>,
+			fee_tier: Option<Permill>,
+			amount_out: BalanceOf<T>,
+			amount_in_max: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(token_in != token_out, Error::<T>::IdenticalTokens);
+			ensure!(amount_out > <BalanceOf<T>>::default(), Error::<T>::AmountZero);
+
+			let mut pool = Self::get_pool(token_in, token_out, fee_tier)?;
+			let (reserve_in, reserve_out) = pool.reserves_for(token_in);
+			let amount_in = pool.get_amount_in(amount_out, reserve_in, reserve_out)?;
+			ensure!(amount_in <= amount_in_max, Error::<T>::ExcessiveInputAmount);
+			ensure!(Self::balance(token_in, &sender) >= amount_in, Error::<T>::InsufficientBalance);
+
+			pool.swap(token_in, amount_in, amount_out, &sender)?;
+			<LiquidityPools<T>>::insert(pool.pair, pool.fee, &pool);
+			Self::deposit_event(Event::Swapped(sender, token_in, amount_in, token_out, amount_out));
+			Ok(())
+		}
 	}
 
 	// Internal functions to be used by this pallet
 	impl<T: Config> Pallet<T> {
+		/// Quote the amount of every asset along `path` for an exact input, using the pool of
+		/// every hop in a fee tier, `DefaultFee` if `None`
+		pub fn get_amounts_out(
+			amount_in: BalanceOf<T>,
+			path: &[MultiAssetIdOf<T>],
+			fee_tier: Option<Permill>,
+		) -> Result<Vec<BalanceOf<T>>, DispatchError> {
+			Self::ensure_valid_path(path)?;
+
+			let mut amounts = Vec::with_capacity(path.len());
+			amounts.push(amount_in);
+			for hop in path.windows(2) {
+				let pool = Self::get_pool(hop[0], hop[1], fee_tier)?;
+				let (reserve_in, reserve_out) = pool.reserves_for(hop[0]);
+				let amount_out = pool.get_amount_out(amounts[amounts.len() - 1], reserve_in, reserve_out)?;
+				amounts.push(amount_out);
+			}
+			Ok(amounts)
+		}
+
+		/// Quote the amount of every asset along `path` for an exact output, using the pool of
+		/// every hop in a fee tier, `DefaultFee` if `None`
+		pub fn get_amounts_in(
+			amount_out: BalanceOf<T>,
+			path: &[MultiAssetIdOf<T>],
+			fee_tier: Option<Permill>,
+		) -> Result<Vec<BalanceOf<T>>, DispatchError> {
+			Self::ensure_valid_path(path)?;
+
+			let mut amounts = vec![<BalanceOf<T>>::default(); path.len()];
+			amounts[path.len() - 1] = amount_out;
+			for i in (1..path.len()).rev() {
+				let pool = Self::get_pool(path[i - 1], path[i], fee_tier)?;
+				let (reserve_in, reserve_out) = pool.reserves_for(path[i - 1]);
+				amounts[i - 1] = pool.get_amount_in(amounts[i], reserve_in, reserve_out)?;
+			}
+			Ok(amounts)
+		}
+
+		/// 1 if the pool of `pair` holds the native currency, the `n` component of the single
+		/// pool weights
+		fn native_pools(pair: &[MultiAssetIdOf<T>; 2]) -> u32 {
+			pair.contains(&MultiAssetId::Native) as u32
+		}
+
+		/// The number of pools a swap along `path` goes through, the `h` component of the path
+		/// swap weights
+		fn hops(path: &[MultiAssetIdOf<T>]) -> u32 {
+			path.len().saturating_sub(1) as u32
+		}
+
+		/// Check that `path` has at least two assets and goes through every pool at most once.
+		/// Every hop is quoted on the reserves from before the swap, which would be stale for a
+		/// pool visited again.
+		fn ensure_valid_path(path: &[MultiAssetIdOf<T>]) -> DispatchResult {
+			ensure!(path.len() >= 2, Error::<T>::InvalidPath);
+			let mut pairs = Vec::with_capacity(path.len() - 1);
+			for hop in path.windows(2) {
+				ensure!(hop[0] != hop[1], Error::<T>::IdenticalTokens);
+				let pair = Pair::<T>::new_pair(hop[0], hop[1]);
+				ensure!(!pairs.contains(&pair), Error::<T>::InvalidPath);
+				pairs.push(pair);
+			}
+			Ok(())
+		}
+
+		/// Execute the `amounts` quoted in `fee_tier` along `path`, which must have passed
+		/// `ensure_valid_path`. The output of every hop is paid straight into the pool of the next
+		/// hop, and the final output to the sender.
+		pub(crate) fn do_swap_along_path(
+			sender: &AccountIdOf<T>,
+			path: &[MultiAssetIdOf<T>],
+			fee_tier: Option<Permill>,
+			amounts: &[BalanceOf<T>],
+		) -> DispatchResult {
+			let mut pools = path
+				.windows(2)
+				.map(|hop| Self::get_pool(hop[0], hop[1], fee_tier))
+				.collect::<Result<Vec<_>, _>>()?;
+			for (i, pool) in pools.iter_mut().enumerate() {
+				pool.update_cumulative_prices()?;
+				pool.apply_swap(path[i], amounts[i], amounts[i + 1])?;
+			}
+
+			Self::transfer(path[0], sender, &pools[0].account, amounts[0])?;
+			for (i, pool) in pools.iter().enumerate() {
+				let to = pools.get(i + 1).map(|next| &next.account).unwrap_or(sender);
+				Self::transfer(path[i + 1], &pool.account, to, amounts[i + 1])?;
+			}
+			for (i, pool) in pools.iter().enumerate() {
+				<LiquidityPools<T>>::insert(pool.pair, pool.fee, pool);
+				<LastTrades<T>>::insert(
+					pool.pair,
+					pool.fee,
+					(sender.clone(), path[i], amounts[i], path[i + 1], amounts[i + 1]),
+				);
+			}
+
+			let hops = path
+				.windows(2)
+				.zip(amounts.windows(2))
+				.map(|(hop, amount)| (hop[0], amount[0], hop[1], amount[1]))
+				.collect();
+			Self::deposit_event(Event::SwappedAlongPath(sender.clone(), hops));
+			Ok(())
+		}
+
+		/// Time-weighted average prices of a pool over at least `window` (in `Time` units) up to
+		/// now, as (price of `token_0` in `token_1`, price of `token_1` in `token_0`).
+		///
+		/// The average starts at the latest observation which is at least `window` old, so the
+		/// actual period can be longer than `window` but never shorter.
+		pub fn twap(
+			token_0: MultiAssetIdOf<T>,
+			token_1: MultiAssetIdOf<T>,
+			fee_tier: Option<Permill>,
+			window: u64,
+		) -> Result<(FixedU128, FixedU128), DispatchError> {
+			let pool = Self::get_pool(token_0, token_1, fee_tier)?;
+			let now = Self::now();
+			let start = now.checked_sub(window).ok_or(Error::<T>::ObservationNotFound)?;
+			let observation = <Observations<T>>::get(pool.pair, pool.fee)
+				.into_iter()
+				.rev()
+				.find(|observation| observation.timestamp <= start && observation.timestamp < now)
+				.ok_or(Error::<T>::ObservationNotFound)?;
+
+			let (price0_cumulative, price1_cumulative) = pool.cumulative_prices_at(pool.reserves(), now);
+			let elapsed = U256::from(now - observation.timestamp);
+			let average = |current: U256, past: U256| -> Result<FixedU128, DispatchError> {
+				let price = current.overflowing_sub(past).0 / elapsed;
+				ensure!(price <= U256::from(u128::MAX), Error::<T>::Overflow);
+				Ok(FixedU128::from_inner(price.low_u128()))
+			};
+			let price0 = average(price0_cumulative, observation.price0_cumulative)?;
+			let price1 = average(price1_cumulative, observation.price1_cumulative)?;
+
+			if token_0 == pool.pair.0 { Ok((price0, price1)) } else { Ok((price1, price0)) }
+		}
+
+		/// The current moment of `Time`
+		fn now() -> u64 {
+			T::Time::now().saturated_into::<u64>()
+		}
+
+		/// Get all liquidity pools
+		pub fn pools() -> Vec<LiquidityPool<T>> {
+			<LiquidityPools<T>>::iter_values().collect()
+		}
+
+		/// Get the LP tokens held by `who` in a pool and the reserves they can be redeemed for,
+		/// as (lp_balance, amt_0, amt_1) in pair order
+		pub fn position(
+			pool: &LiquidityPool<T>,
+			who: &AccountIdOf<T>,
+		) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
+			let lp_balance = T::Tokens::balance(pool.id, who);
+			let issuance = T::Tokens::total_issuance(pool.id);
+			if lp_balance == <BalanceOf<T>>::default() {
+				return Ok((lp_balance, Default::default(), Default::default()))
+			}
+
+			let reserves = pool.reserves();
+			let amount_0 = LiquidityPool::<T>::mul_div(reserves.0, lp_balance, issuance)?;
+			let amount_1 = LiquidityPool::<T>::mul_div(reserves.1, lp_balance, issuance)?;
+			Ok((lp_balance, amount_0, amount_1))
+		}
+
+		/// Get the last swap through the pool of two tokens in a fee tier
+		pub fn last_trade(
+			token_0: MultiAssetIdOf<T>,
+			token_1: MultiAssetIdOf<T>,
+			fee_tier: Option<Permill>,
+		) -> Option<(AccountIdOf<T>, MultiAssetIdOf<T>, BalanceOf<T>, MultiAssetIdOf<T>, BalanceOf<T>)> {
+			let pair = Pair::<T>::new_pair(token_0, token_1);
+			<LastTrades<T>>::get(pair, fee_tier.unwrap_or_else(T::DefaultFee::get))
+		}
+
+		/// Get the storage keys written on every reserve change of the pool of two tokens in a
+		/// fee tier, so clients can watch them. Empty if there is no such pool.
+		pub fn pool_storage_keys(
+			token_0: MultiAssetIdOf<T>,
+			token_1: MultiAssetIdOf<T>,
+			fee_tier: Option<Permill>,
+		) -> Vec<Vec<u8>> {
+			match Self::get_pool(token_0, token_1, fee_tier) {
+				Ok(pool) => vec![
+					<LiquidityPools<T>>::hashed_key_for(pool.pair, pool.fee),
+					<LastTrades<T>>::hashed_key_for(pool.pair, pool.fee),
+				],
+				Err(_) => Vec::new(),
+			}
+		}
+
+		/// Get the liquidity pool of two tokens in a fee tier, regardless of their order
+		pub fn get_pool(
+			token_0: MultiAssetIdOf<T>,
+			token_1: MultiAssetIdOf<T>,
+			fee_tier: Option<Permill>,
+		) -> Result<LiquidityPool<T>, DispatchError> {
+			let pair = Pair::<T>::new_pair(token_0, token_1);
+			let fee = fee_tier.unwrap_or_else(T::DefaultFee::get);
+			<LiquidityPools<T>>::get(pair, fee).ok_or_else(|| Error::<T>::PoolNotFound.into())
+		}
+
+		/// Resolve an optional fee tier to one of the configured `FeeTiers`
+		fn fee_tier(fee_tier: Option<Permill>) -> Result<Permill, DispatchError> {
+			let fee = fee_tier.unwrap_or_else(T::DefaultFee::get);
+			ensure!(T::FeeTiers::get().contains(&fee), Error::<T>::InvalidFeeTier);
+			Ok(fee)
+		}
+
 		/// Get the balance of a token given an account
-		fn balance(id: AssetIdOf<T>, who: &AccountIdOf<T>) -> BalanceOf<T> {
-			if id == T::NativeTokenId::get() {
-				T::NativeCurrency::total_balance(who)
-			} else {
+		pub fn balance(id: MultiAssetIdOf<T>, who: &AccountIdOf<T>) -> BalanceOf<T> {
+			match id {
+				MultiAssetId::Native => T::NativeCurrency::free_balance(who),
 				// Otherwise use asset balance
-				T::Tokens::balance(id, &who)
+				MultiAssetId::Asset(id) => T::Tokens::balance(id, who),
+			}
+		}
+
+		/// Move a token between two accounts, through `NativeCurrency` for the native currency
+		pub fn transfer(
+			id: MultiAssetIdOf<T>,
+			from: &AccountIdOf<T>,
+			to: &AccountIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			match id {
+				MultiAssetId::Native =>
+					T::NativeCurrency::transfer(from, to, amount, ExistenceRequirement::KeepAlive),
+				MultiAssetId::Asset(id) => T::Tokens::teleport(id, from, to, amount).map(|_| ()),
+			}
+		}
+
+		/// Check whether a token exists, the native currency always does
+		pub fn exists(id: MultiAssetIdOf<T>) -> bool {
+			match id {
+				MultiAssetId::Native => true,
+				MultiAssetId::Asset(id) => T::exists(id),
+			}
+		}
+
+		/// Get the symbol of a token
+		fn symbol(id: MultiAssetIdOf<T>) -> Vec<u8> {
+			match id {
+				MultiAssetId::Native => T::NativeSymbol::get(),
+				MultiAssetId::Asset(id) => T::Tokens::symbol(&id),
 			}
 		}
 	}
@@ -274,8 +1159,11 @@ pub mod pallet {
 	// Configuration of the DEX state at genesis
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
-		/// Genesis liquidity pools: ((amount, asset), (amount, asset), liquidity provider)
-		pub liquidity_pools: Vec<((BalanceOf<T>, AssetIdOf<T>), (BalanceOf<T>, AssetIdOf<T>), AccountIdOf<T>)>,
+		/// Genesis liquidity pools: ((amount, asset), (amount, asset), liquidity provider, fee tier).
+		/// The provider must hold both amounts, plus the existential deposit of the pool account
+		/// for a native pool, and is credited with the LP tokens. No pool creation deposit is
+		/// reserved.
+		pub liquidity_pools: Vec<((BalanceOf<T>, MultiAssetIdOf<T>), (BalanceOf<T>, MultiAssetIdOf<T>), AccountIdOf<T>, Permill)>,
 	}
 
 	#[cfg(feature = "std")]
@@ -288,19 +1176,26 @@ pub mod pallet {
 	#[pallet::genesis_build]
 	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
 		fn build(&self) {
-				for (token_0, token_1, sender) in &self.liquidity_pools {
-					let pair = Pair::<T>::new_pair(token_0.1,token_1.1,);
+			for (token_0, token_1, provider, fee) in &self.liquidity_pools {
+				assert!(token_0.1 != token_1.1, "Genesis pools need two different tokens");
+				assert!(
+					Pallet::<T>::exists(token_0.1) && Pallet::<T>::exists(token_1.1),
+					"Genesis pool tokens must exist"
+				);
+				let pair = Pair::<T>::new_pair(token_0.1, token_1.1);
+				let amounts = if token_0.1 == pair.0 { (token_0.0, token_1.0) } else { (token_1.0, token_0.0) };
 
-					let new_pool = LiquidityPool::<T>::new_liquidity_pool(pair)
-						.expect("Should be able to create new LiquidityPool during genesis");
-					
-					let pallet_id = T::PalletId::get();
+				let fee = *fee;
+				assert!(T::FeeTiers::get().contains(&fee), "Genesis pool fee must be one of the FeeTiers");
+				assert!(!LiquidityPools::<T>::contains_key(pair, fee), "Duplicate genesis pool");
+				let mut new_pool = LiquidityPool::<T>::new_liquidity_pool(pair, fee, provider)
+					.expect("Should be able to create new LiquidityPool during genesis");
 
-					new_pool.add_liquidity((token_0.0, token_1.0), &pallet_id.into_account_truncating())
-						.expect("Should be able to add liquidity during genesis");
+				new_pool.add_liquidity(amounts, Default::default(), provider)
+					.expect("Should be able to add liquidity during genesis");
 
-					LiquidityPools::<T>::insert(pair, new_pool);
-				}
+				LiquidityPools::<T>::insert(pair, fee, new_pool);
 			}
 		}
 	}
+}
@@ -1,4 +1,5 @@
 use crate as pallet_dex;
+use crate::MultiAssetId;
 use frame_support::{parameter_types, PalletId};
 
 use frame_support::traits::{ConstU16, ConstU32, ConstU64, ConstU128};
@@ -8,7 +9,7 @@ use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
-	BuildStorage,
+	BuildStorage, Permill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -24,7 +25,8 @@ frame_support::construct_runtime!(
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Assets: pallet_assets::{Pallet, Call, Storage, Config<T>, Event<T>},
-		Dex: pallet_dex::{Pallet, Call, Storage, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
+		Dex: pallet_dex::{Pallet, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -83,15 +85,39 @@ impl pallet_balances::Config for Test {
 	type Balance = Balance;
 	type Event = Event;
 	type DustRemoval = ();
-	type ExistentialDeposit = ConstU128<1>;
+	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 	type WeightInfo = ();
 }
 
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = ConstU64<1>;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub static ExistentialDeposit: Balance = 1;
+}
+
 parameter_types! {
 	pub const Decks: PalletId = PalletId(*b"dotdecks");
 	pub const TokenMinimumBalance: u32 = 1; // Must be greater than 0 (existential deposit)
+	pub const MinimumLiquidity: u32 = 1_000; // Must be at least TokenMinimumBalance
 	pub const TokenDecimals: u8 = 12;
+	pub const DefaultFee: Permill = Permill::from_parts(3_000); // 0.3%
+	pub FeeTiers: Vec<Permill> = vec![
+		Permill::from_parts(500), // 0.05%
+		Permill::from_parts(3_000), // 0.3%
+		Permill::from_parts(10_000), // 1%
+	];
+	pub const ProtocolFeeReceiver: AccountId = 99;
+	pub const ProtocolFeeDenominator: u32 = 6; // 1/6 of fee growth, as in Uniswap V2
+	pub const MaxHops: u32 = 4; // Longest swap path, in assets
+	pub NativeSymbol: Vec<u8> = b"UNIT".to_vec();
+	pub const PoolCreationDeposit: Balance = 100;
+	pub const MaxObservations: u32 = 24;
 }
 
 impl pallet_dex::Config for Test {
@@ -99,47 +125,124 @@ impl pallet_dex::Config for Test {
 	type AssetId = AssetId;
 	type Tokens = Assets;
 	type PalletId = Decks;
+	type DefaultFee = DefaultFee;
+	type FeeTiers = FeeTiers;
+	type ProtocolFeeReceiver = ProtocolFeeReceiver;
+	type ProtocolFeeDenominator = ProtocolFeeDenominator;
+	type ProtocolFeeOrigin = EnsureRoot<AccountId>;
+	type MaxHops = MaxHops;
 	type LpTokenMinimumBalance = TokenMinimumBalance;
+	type MinimumLiquidity = MinimumLiquidity;
 	type LpTokenDecimals = TokenDecimals;
 	type NativeCurrency = Balances;
-	type NativeTokenId = ();
+	type NativeSymbol = NativeSymbol;
+	type PoolCreationDeposit = PoolCreationDeposit;
+	type Time = Timestamp;
+	type MaxObservations = MaxObservations;
+	type WeightInfo = ();
 	
 	fn exists(id: Self::AssetId) -> bool {
 		Assets::maybe_total_supply(id).is_some()
 	}
 }
 
-// Build genesis storage according to the mock runtime.
-pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
-	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
-	GenesisConfig {
-		balances: BalancesConfig { 
-			balances: vec![
-				(1, 10_000_000)
-			],
-		},
-		..Default::default()
-
-		// Want to ideally map the AssetConfig here to the one in node/chain_spec.rs
-		// and thereafter simulate state transitions with my tests, but didn't manage
-
-		// assets: AssetConfig {
-		// 	assets: vec![
-		// 		(TOKEN_0,),
-		// 		(TOKEN_1,),
-		// 		()
-		// 	],
-		// 	balances: vec! [
-
-		// 	],
-		// 	metadata: vec![],
-		// }
+pub const ALICE: AccountId = 1; // Owns the assets and provides the pools' liquidity
+pub const BOB: AccountId = 2; // Holds assets to trade with
+pub const TKA: AssetId = 1;
+pub const TKB: AssetId = 2;
+pub const TAB: AssetId = 3;
+
+/// The liquidity Alice provides to each side of every pool built by `with_pools`
+pub const POOL_LIQUIDITY: Balance = 100_000;
+
+// Builds the genesis storage of the mock runtime, optionally with assets and pools between them.
+// Only the asset ids and symbols and the pool pairs match the dev chain spec. The pools are
+// funded with `POOL_LIQUIDITY` of each side, not the reserves of the dev chain spec.
+#[derive(Default)]
+pub struct ExtBuilder {
+	assets: bool,
+	pools: bool,
+	existential_deposit: Option<Balance>,
+}
+
+impl ExtBuilder {
+	/// Create TKA, TKB and TAB owned by Alice, and give Alice and Bob 1_000_000 of each
+	pub fn with_assets(mut self) -> Self {
+		self.assets = true;
+		self
+	}
 
+	/// Create the TKA/TKB, UNIT/TKA and UNIT/TKB pools at genesis in the default fee tier, each
+	/// funded with `POOL_LIQUIDITY` of both assets by Alice. Implies `with_assets`.
+	pub fn with_pools(mut self) -> Self {
+		self.assets = true;
+		self.pools = true;
+		self
 	}
-	.assimilate_storage(&mut t)
-	.unwrap();
 
-	let mut ext = sp_io::TestExternalities::new(t);
-	ext.execute_with(|| System::set_block_number(1));
-	ext
+	/// Use an existential deposit other than 1 for the native currency
+	pub fn with_existential_deposit(mut self, existential_deposit: Balance) -> Self {
+		self.existential_deposit = Some(existential_deposit);
+		self
+	}
+
+	pub fn build(self) -> sp_io::TestExternalities {
+		ExistentialDeposit::set(self.existential_deposit.unwrap_or(1));
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let assets = if self.assets {
+			AssetsConfig {
+				assets: vec![(TKA, ALICE, true, 1), (TKB, ALICE, true, 1), (TAB, ALICE, true, 1)],
+				metadata: vec![
+					(TKA, b"TOKEN_A".to_vec(), b"TKA".to_vec(), 8),
+					(TKB, b"TOKEN_B".to_vec(), b"TKB".to_vec(), 8),
+					(TAB, b"TOKA_TOKB".to_vec(), b"TAB".to_vec(), 8),
+				],
+				accounts: vec![
+					(TKA, ALICE, 1_000_000),
+					(TKA, BOB, 1_000_000),
+					(TKB, ALICE, 1_000_000),
+					(TKB, BOB, 1_000_000),
+					(TAB, ALICE, 1_000_000),
+					(TAB, BOB, 1_000_000),
+				],
+			}
+		} else {
+			Default::default()
+		};
+		let dex = if self.pools {
+			let (tka, tkb, native) =
+				(MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB), MultiAssetId::Native);
+			DexConfig {
+				liquidity_pools: vec![
+					((POOL_LIQUIDITY, tka), (POOL_LIQUIDITY, tkb), ALICE, DefaultFee::get()),
+					((POOL_LIQUIDITY, native), (POOL_LIQUIDITY, tka), ALICE, DefaultFee::get()),
+					((POOL_LIQUIDITY, native), (POOL_LIQUIDITY, tkb), ALICE, DefaultFee::get()),
+				],
+			}
+		} else {
+			Default::default()
+		};
+		GenesisConfig {
+			balances: BalancesConfig {
+				balances: vec![
+					(ALICE, 10_000_000),
+					(BOB, 10_000_000),
+				],
+			},
+			assets,
+			dex,
+			..Default::default()
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
+
+// Build genesis storage according to the mock runtime.
+pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
+	ExtBuilder::default().build()
 }
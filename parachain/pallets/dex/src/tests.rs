@@ -1,7 +1,12 @@
 use super::*;
 use crate::mock::*;
 use crate::LiquidityPool;
-use frame_support::assert_ok;
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{tokens::fungibles::InspectMetadata, Get},
+    BoundedVec,
+};
+use sp_runtime::{traits::IntegerSquareRoot, FixedU128, Permill};
 
 const ADMIN: u128 = 1; // root account
 const TOKEN_0: u32 = 1; // The first token AssetId
@@ -11,17 +16,503 @@ const TOKEN_1: u32 = 2; // The second token AssetId
 #[test]
 fn new_liquidity_pool() {
     new_test_ext().execute_with(|| {
-        assert_ok!(<LiquidityPool<Test>>::new_liquidity_pool((TOKEN_0, TOKEN_1)));
+        assert_ok!(<LiquidityPool<Test>>::new_liquidity_pool(
+            (MultiAssetId::Asset(TOKEN_0), MultiAssetId::Asset(TOKEN_1)),
+            DefaultFee::get(),
+            &ADMIN,
+        ));
     });
 }
 
-// Need to fix this test by making sure that the GenesisConfig bootstraps with correct balances for each asset so the ADMIN account
-// #[test]
-// fn adds_liquidity() {
-//     new_test_ext().execute_with(|| {
-//         assert_ok!(Assets::force_create(Origin::root(), TOKEN_0, ADMIN, true, 250u128)); 
-//         assert_ok!(Assets::force_create(Origin::root(), TOKEN_1, ADMIN, true, 250u128));
-//         let pool = <LiquidityPool<Test>>::new_liquidity_pool((TOKEN_0, TOKEN_1)).unwrap();
-//         assert_ok!(pool.add_liquidity((100u128, 100u128), &ADMIN));
-//     });
-// }
+// The TWAP over a window equals the spot price when the reserves didn't change during it
+#[test]
+fn twap_follows_pool_price() {
+    new_test_ext().execute_with(|| {
+        let native = MultiAssetId::Native;
+        let token = MultiAssetId::Asset(TOKEN_0);
+        assert_ok!(Assets::force_create(Origin::root(), TOKEN_0, ADMIN, true, 1));
+        assert_ok!(Assets::mint(Origin::signed(ADMIN), TOKEN_0, ADMIN, 1_000_000));
+
+        Timestamp::set_timestamp(1_000);
+        assert_ok!(Dex::create_pool(Origin::signed(ADMIN), native, token, None));
+        assert_ok!(Dex::add_liquidity(Origin::signed(ADMIN), 100_000, native, 200_000, token, None, 0, 0));
+
+        // The first observation is recorded on the first reserve change after creation
+        Timestamp::set_timestamp(2_000);
+        assert_ok!(Dex::swap_exact_tokens_for_tokens(Origin::signed(ADMIN), native, token, None, 1_000, 0));
+        assert_eq!(Observations::<Test>::get((native, token), DefaultFee::get()).len(), 1);
+
+        Timestamp::set_timestamp(3_000);
+        let pool = LiquidityPools::<Test>::get((native, token), DefaultFee::get()).unwrap();
+        let (reserve_0, reserve_1) = pool.reserves();
+        assert_eq!(
+            Dex::twap(native, token, None, 1_000).unwrap(),
+            (
+                FixedU128::from_inner(reserve_1 * 1_000_000_000_000_000_000 / reserve_0),
+                FixedU128::from_inner(reserve_0 * 1_000_000_000_000_000_000 / reserve_1),
+            )
+        );
+        assert_noop!(Dex::twap(native, token, None, 2_000), Error::<Test>::ObservationNotFound);
+    });
+}
+
+// Tokens sent straight to the pool account are not reserves, so they can't move the TWAP
+#[test]
+fn donation_does_not_move_twap() {
+    new_test_ext().execute_with(|| {
+        let native = MultiAssetId::Native;
+        let token = MultiAssetId::Asset(TOKEN_0);
+        assert_ok!(Assets::force_create(Origin::root(), TOKEN_0, ADMIN, true, 1));
+        assert_ok!(Assets::mint(Origin::signed(ADMIN), TOKEN_0, ADMIN, 1_000_000));
+
+        Timestamp::set_timestamp(1_000);
+        assert_ok!(Dex::create_pool(Origin::signed(ADMIN), native, token, None));
+        assert_ok!(Dex::add_liquidity(Origin::signed(ADMIN), 100_000, native, 200_000, token, None, 0, 0));
+        Timestamp::set_timestamp(2_000);
+        assert_ok!(Dex::swap_exact_tokens_for_tokens(Origin::signed(ADMIN), native, token, None, 1_000, 0));
+        let pool = Dex::get_pool(native, token, None).unwrap();
+        let (reserve_0, reserve_1) = pool.reserves();
+
+        // Donate to the pool and trigger an accumulator update in the same block
+        Timestamp::set_timestamp(3_000);
+        assert_ok!(Assets::transfer(Origin::signed(ADMIN), TOKEN_0, pool.account, 500_000));
+        assert_eq!(Dex::get_pool(native, token, None).unwrap().reserves(), (reserve_0, reserve_1));
+        assert_ok!(Dex::swap_exact_tokens_for_tokens(Origin::signed(ADMIN), native, token, None, 1_000, 0));
+
+        assert_eq!(
+            Dex::twap(native, token, None, 1_000).unwrap(),
+            (
+                FixedU128::from_inner(reserve_1 * 1_000_000_000_000_000_000 / reserve_0),
+                FixedU128::from_inner(reserve_0 * 1_000_000_000_000_000_000 / reserve_1),
+            )
+        );
+    });
+}
+
+// The mock genesis mirrors the assets of the development chain spec
+#[test]
+fn genesis_creates_assets() {
+    ExtBuilder::default().with_assets().build().execute_with(|| {
+        assert_eq!(Dex::balance(MultiAssetId::Asset(TKA), &ALICE), 1_000_000);
+        assert_eq!(Dex::balance(MultiAssetId::Asset(TAB), &BOB), 1_000_000);
+        assert_eq!(<Assets as InspectMetadata<AccountId>>::symbol(&TKB), b"TKB".to_vec());
+        assert_eq!(<Assets as InspectMetadata<AccountId>>::decimals(&TKB), 8);
+        assert!(Dex::pools().is_empty());
+    });
+}
+
+// The first deposit mints sqrt(a0 * a1) LP tokens, minus the locked minimum liquidity
+#[test]
+fn adds_liquidity() {
+    ExtBuilder::default().with_assets().build().execute_with(|| {
+        let (tka, tkb) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB));
+        assert_ok!(Dex::create_pool(Origin::signed(ALICE), tka, tkb, None));
+        assert_ok!(Dex::add_liquidity(Origin::signed(ALICE), 100_000, tka, 400_000, tkb, None, 0, 0));
+
+        let pool = Dex::get_pool(tka, tkb, None).unwrap();
+        assert_eq!(pool.reserves(), (100_000, 400_000));
+        assert_eq!(Assets::balance(pool.id, ALICE), 200_000 - MinimumLiquidity::get() as u128);
+        assert_eq!(Assets::total_supply(pool.id), 200_000);
+        assert_eq!(Dex::balance(tka, &ALICE), 900_000);
+        assert_eq!(Dex::balance(tkb, &ALICE), 600_000);
+        System::assert_last_event(mock::Event::Dex(crate::Event::LiquidityAdded(tka, 100_000, tkb, 400_000)));
+    });
+}
+
+// Later deposits only pull the amounts matching the reserve ratio
+#[test]
+fn adds_liquidity_at_reserve_ratio() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB));
+        // Tokens given in reverse pair order are mapped back to the caller's order
+        assert_ok!(Dex::add_liquidity(Origin::signed(BOB), 50_000, tkb, 10_000, tka, None, 0, 0));
+
+        let pool = Dex::get_pool(tka, tkb, None).unwrap();
+        assert_eq!(pool.reserves(), (110_000, 110_000));
+        assert_eq!(Assets::balance(pool.id, BOB), 10_000);
+        assert_eq!(Dex::balance(tka, &BOB), 990_000);
+        assert_eq!(Dex::balance(tkb, &BOB), 990_000);
+        System::assert_last_event(mock::Event::Dex(crate::Event::LiquidityAdded(tkb, 10_000, tka, 10_000)));
+
+        // The minimum of the counterpart can't be met at this ratio
+        assert_noop!(
+            Dex::add_liquidity(Origin::signed(BOB), 10_000, tka, 50_000, tkb, None, 0, 20_000),
+            Error::<Test>::InsufficientOutputAmount
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_fails_on_invalid_input() {
+    ExtBuilder::default().with_assets().build().execute_with(|| {
+        let (tka, tkb) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB));
+        assert_noop!(
+            Dex::add_liquidity(Origin::signed(ALICE), 1_000, tka, 1_000, tka, None, 0, 0),
+            Error::<Test>::IdenticalTokens
+        );
+        assert_noop!(
+            Dex::add_liquidity(Origin::signed(ALICE), 0, tka, 1_000, tkb, None, 0, 0),
+            Error::<Test>::AmountZero
+        );
+        assert_noop!(
+            Dex::add_liquidity(Origin::signed(ALICE), 2_000_000, tka, 1_000, tkb, None, 0, 0),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_noop!(
+            Dex::add_liquidity(Origin::signed(ALICE), 1_000, tka, 1_000, tkb, None, 0, 0),
+            Error::<Test>::PoolNotFound
+        );
+    });
+}
+
+#[test]
+fn create_pool_fails_on_invalid_input() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB));
+        assert_noop!(
+            Dex::create_pool(Origin::signed(ALICE), tkb, tka, None),
+            Error::<Test>::PoolAlreadyExists
+        );
+        assert_noop!(
+            Dex::create_pool(Origin::signed(ALICE), tka, MultiAssetId::Asset(9), None),
+            Error::<Test>::NonExistentToken
+        );
+        assert_noop!(
+            Dex::create_pool(Origin::signed(ALICE), tka, tkb, Some(Permill::from_parts(1))),
+            Error::<Test>::InvalidFeeTier
+        );
+        // The same pair can have a pool in another fee tier
+        assert_ok!(Dex::create_pool(Origin::signed(ALICE), tka, tkb, Some(Permill::from_parts(500))));
+    });
+}
+
+// Genesis pools credit the LP tokens to the provider
+#[test]
+fn genesis_creates_pools() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        assert_eq!(Dex::pools().len(), 3);
+        let (tka, native) = (MultiAssetId::Asset(TKA), MultiAssetId::Native);
+        let pool = Dex::get_pool(tka, native, None).unwrap();
+        assert_eq!(pool.reserves(), (POOL_LIQUIDITY, POOL_LIQUIDITY));
+        assert_eq!(
+            Dex::position(&pool, &ALICE).unwrap(),
+            (POOL_LIQUIDITY - MinimumLiquidity::get() as u128, POOL_LIQUIDITY - 1_000, POOL_LIQUIDITY - 1_000)
+        );
+        assert_eq!(Dex::balance(tka, &ALICE), 1_000_000 - 2 * POOL_LIQUIDITY);
+    });
+}
+
+// Burning LP tokens returns the pro rata share of both reserves
+#[test]
+fn removes_liquidity() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB));
+        let pool = Dex::get_pool(tka, tkb, None).unwrap();
+        let lp_balance = Assets::balance(pool.id, ALICE);
+        assert_eq!(lp_balance, POOL_LIQUIDITY - MinimumLiquidity::get() as u128);
+
+        assert_ok!(Dex::remove_liquidity(Origin::signed(ALICE), tka, tkb, None, lp_balance, 0, 0));
+
+        // Only the locked minimum liquidity stays in the pool
+        let locked = MinimumLiquidity::get() as u128;
+        assert_eq!(Dex::get_pool(tka, tkb, None).unwrap().reserves(), (locked, locked));
+        assert_eq!(Assets::balance(pool.id, ALICE), 0);
+        // Alice funded two pools with TKA
+        assert_eq!(Dex::balance(tka, &ALICE), 1_000_000 - 2 * POOL_LIQUIDITY + lp_balance);
+        System::assert_last_event(mock::Event::Dex(crate::Event::LiquidityRemoved(tka, lp_balance, tkb, lp_balance)));
+    });
+}
+
+// The pool account keeps its existential deposit on top of the native reserve, so the reserve
+// can be swapped below the deposit and fully withdrawn
+#[test]
+fn native_reserve_excludes_existential_deposit() {
+    let ed = 50_000;
+    ExtBuilder::default().with_pools().with_existential_deposit(ed).build().execute_with(|| {
+        let (tka, native) = (MultiAssetId::Asset(TKA), MultiAssetId::Native);
+        let pool = Dex::get_pool(tka, native, None).unwrap();
+        assert_eq!(pool.reserves(), (POOL_LIQUIDITY, POOL_LIQUIDITY));
+        assert_eq!(Balances::free_balance(pool.account), POOL_LIQUIDITY + ed);
+
+        assert_ok!(Dex::swap_tokens_for_exact_tokens(Origin::signed(BOB), tka, native, None, 90_000, u128::MAX));
+        let pool = Dex::get_pool(tka, native, None).unwrap();
+        assert_eq!(pool.reserves().0, 10_000);
+        assert_eq!(Balances::free_balance(pool.account), 10_000 + ed);
+
+        let lp_balance = Assets::balance(pool.id, ALICE);
+        assert_ok!(Dex::remove_liquidity(Origin::signed(ALICE), tka, native, None, lp_balance, 0, 0));
+        let pool = Dex::get_pool(tka, native, None).unwrap();
+        assert_eq!(Balances::free_balance(pool.account), pool.reserves().0 + ed);
+        assert!(pool.reserves().0 > 0);
+    });
+}
+
+#[test]
+fn remove_liquidity_fails_on_invalid_input() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB));
+        assert_noop!(
+            Dex::remove_liquidity(Origin::signed(ALICE), tka, tka, None, 1_000, 0, 0),
+            Error::<Test>::IdenticalTokens
+        );
+        assert_noop!(
+            Dex::remove_liquidity(Origin::signed(ALICE), tka, tkb, None, 0, 0, 0),
+            Error::<Test>::AmountZero
+        );
+        assert_noop!(
+            Dex::remove_liquidity(Origin::signed(BOB), tka, tkb, None, 1_000, 0, 0),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_noop!(
+            Dex::remove_liquidity(Origin::signed(ALICE), tka, tkb, None, 1_000, 2_000, 0),
+            Error::<Test>::InsufficientOutputAmount
+        );
+    });
+}
+
+// An exact input pays out reserve_out * in * 0.997 / (reserve_in + in * 0.997)
+#[test]
+fn swaps_exact_tokens_for_tokens() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB));
+        assert_noop!(
+            Dex::swap_exact_tokens_for_tokens(Origin::signed(BOB), tka, tkb, None, 1_000, 988),
+            Error::<Test>::InsufficientOutputAmount
+        );
+        assert_ok!(Dex::swap_exact_tokens_for_tokens(Origin::signed(BOB), tka, tkb, None, 1_000, 987));
+
+        assert_eq!(Dex::balance(tka, &BOB), 999_000);
+        assert_eq!(Dex::balance(tkb, &BOB), 1_000_987);
+        assert_eq!(Dex::get_pool(tka, tkb, None).unwrap().reserves(), (101_000, 99_013));
+        assert_eq!(Dex::last_trade(tkb, tka, None), Some((BOB, tka, 1_000, tkb, 987)));
+        System::assert_last_event(mock::Event::Dex(crate::Event::Swapped(BOB, tka, 1_000, tkb, 987)));
+    });
+}
+
+// An exact output costs reserve_in * out / ((reserve_out - out) * 0.997), rounded up
+#[test]
+fn swaps_tokens_for_exact_tokens() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tkb, native) = (MultiAssetId::Asset(TKB), MultiAssetId::Native);
+        assert_noop!(
+            Dex::swap_tokens_for_exact_tokens(Origin::signed(BOB), native, tkb, None, 1_000, 1_014),
+            Error::<Test>::ExcessiveInputAmount
+        );
+        assert_ok!(Dex::swap_tokens_for_exact_tokens(Origin::signed(BOB), native, tkb, None, 1_000, 1_015));
+
+        assert_eq!(Dex::balance(native, &BOB), 10_000_000 - 1_015);
+        assert_eq!(Dex::balance(tkb, &BOB), 1_001_000);
+        System::assert_last_event(mock::Event::Dex(crate::Event::Swapped(BOB, native, 1_015, tkb, 1_000)));
+    });
+}
+
+#[test]
+fn swap_fails_on_invalid_input() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb, tab) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB), MultiAssetId::Asset(TAB));
+        assert_noop!(
+            Dex::swap_exact_tokens_for_tokens(Origin::signed(BOB), tka, tka, None, 1_000, 0),
+            Error::<Test>::IdenticalTokens
+        );
+        assert_noop!(
+            Dex::swap_exact_tokens_for_tokens(Origin::signed(BOB), tka, tkb, None, 0, 0),
+            Error::<Test>::AmountZero
+        );
+        assert_noop!(
+            Dex::swap_exact_tokens_for_tokens(Origin::signed(BOB), tka, tkb, None, 2_000_000, 0),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_noop!(
+            Dex::swap_tokens_for_exact_tokens(Origin::signed(BOB), tka, tkb, None, 0, 1_000),
+            Error::<Test>::AmountZero
+        );
+        assert_noop!(
+            Dex::swap_tokens_for_exact_tokens(Origin::signed(BOB), tka, tkb, None, POOL_LIQUIDITY, u128::MAX),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_noop!(
+            Dex::swap_exact_tokens_for_tokens(Origin::signed(BOB), tka, tab, None, 1_000, 0),
+            Error::<Test>::PoolNotFound
+        );
+    });
+}
+
+// A path swap chains the exact input quotes of every hop
+#[test]
+fn swaps_along_path() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb, native) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB), MultiAssetId::Native);
+        let path: BoundedVec<_, MaxHops> = vec![tka, native, tkb].try_into().unwrap();
+        assert_eq!(Dex::get_amounts_out(1_000, &path, None).unwrap(), vec![1_000, 987, 974]);
+
+        assert_ok!(Dex::swap_exact_in_along_path(Origin::signed(BOB), path, None, 1_000, 974));
+
+        assert_eq!(Dex::balance(tka, &BOB), 999_000);
+        assert_eq!(Dex::balance(native, &BOB), 10_000_000);
+        assert_eq!(Dex::balance(tkb, &BOB), 1_000_974);
+        assert_eq!(Dex::get_pool(native, tka, None).unwrap().reserves(), (99_013, 101_000));
+        assert_eq!(Dex::get_pool(native, tkb, None).unwrap().reserves(), (100_987, 99_026));
+
+        let single: BoundedVec<_, MaxHops> = vec![tka].try_into().unwrap();
+        assert_noop!(
+            Dex::swap_exact_in_along_path(Origin::signed(BOB), single, None, 1_000, 0),
+            Error::<Test>::InvalidPath
+        );
+    });
+}
+
+// An exact output path swap chains the exact output quotes of every hop backwards
+#[test]
+fn swaps_exact_out_along_path() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb, native) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB), MultiAssetId::Native);
+        let path: BoundedVec<_, MaxHops> = vec![tka, native, tkb].try_into().unwrap();
+        assert_eq!(Dex::get_amounts_in(1_000, &path, None).unwrap(), vec![1_030, 1_015, 1_000]);
+
+        assert_noop!(
+            Dex::swap_exact_out_along_path(Origin::signed(BOB), path.clone(), None, 1_000, 1_029),
+            Error::<Test>::ExcessiveInputAmount
+        );
+        assert_ok!(Dex::swap_exact_out_along_path(Origin::signed(BOB), path, None, 1_000, 1_030));
+
+        assert_eq!(Dex::balance(tka, &BOB), 1_000_000 - 1_030);
+        assert_eq!(Dex::balance(native, &BOB), 10_000_000);
+        assert_eq!(Dex::balance(tkb, &BOB), 1_001_000);
+        assert_eq!(Dex::get_pool(native, tka, None).unwrap().reserves(), (98_985, 101_030));
+        assert_eq!(Dex::get_pool(native, tkb, None).unwrap().reserves(), (101_015, 99_000));
+    });
+}
+
+// A path swap executes the quote of the fee tier it is given, and leaves the other tiers alone
+#[test]
+fn swaps_along_path_in_fee_tier() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb, native) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB), MultiAssetId::Native);
+        let one_percent = Some(Permill::from_percent(1));
+        for token in [tka, tkb] {
+            assert_ok!(Dex::create_pool(Origin::signed(ALICE), native, token, one_percent));
+            assert_ok!(Dex::add_liquidity(Origin::signed(ALICE), 100_000, native, 100_000, token, one_percent, 0, 0));
+        }
+        let path: BoundedVec<_, MaxHops> = vec![tka, native, tkb].try_into().unwrap();
+        assert_eq!(Dex::get_amounts_out(1_000, &path, one_percent).unwrap(), vec![1_000, 980, 960]);
+
+        assert_noop!(
+            Dex::swap_exact_in_along_path(Origin::signed(BOB), path.clone(), Some(Permill::from_parts(500)), 1_000, 0),
+            Error::<Test>::PoolNotFound
+        );
+        assert_ok!(Dex::swap_exact_in_along_path(Origin::signed(BOB), path, one_percent, 1_000, 960));
+
+        assert_eq!(Dex::balance(tkb, &BOB), 1_000_960);
+        assert_eq!(Dex::get_pool(native, tka, one_percent).unwrap().reserves(), (99_020, 101_000));
+        assert_eq!(Dex::get_pool(native, tkb, one_percent).unwrap().reserves(), (100_980, 99_040));
+        assert_eq!(Dex::get_pool(native, tka, None).unwrap().reserves(), (POOL_LIQUIDITY, POOL_LIQUIDITY));
+        assert_eq!(Dex::get_pool(native, tkb, None).unwrap().reserves(), (POOL_LIQUIDITY, POOL_LIQUIDITY));
+    });
+}
+
+// Every hop is quoted on the reserves from before the swap, so a path can't reuse a pool
+#[test]
+fn swap_path_fails_through_the_same_pool_twice() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb, native) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB), MultiAssetId::Native);
+        let round_trip: BoundedVec<_, MaxHops> = vec![tka, tkb, tka].try_into().unwrap();
+        assert_noop!(
+            Dex::swap_exact_in_along_path(Origin::signed(BOB), round_trip, None, 1_000, 0),
+            Error::<Test>::InvalidPath
+        );
+        let cycle: BoundedVec<_, MaxHops> = vec![tkb, native, tka, tkb].try_into().unwrap();
+        assert_ok!(Dex::get_amounts_out(1_000, &cycle, None));
+        let revisit: BoundedVec<_, MaxHops> = vec![native, tka, tkb, tka].try_into().unwrap();
+        assert_noop!(
+            Dex::swap_exact_out_along_path(Origin::signed(BOB), revisit, None, 1_000, u128::MAX),
+            Error::<Test>::InvalidPath
+        );
+    });
+}
+
+// Only ProtocolFeeOrigin can switch the protocol fee, which starts from the current sqrt(k)
+#[test]
+fn set_protocol_fee_requires_origin() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB));
+        assert_noop!(
+            Dex::set_protocol_fee(Origin::signed(ALICE), tka, tkb, None, true),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Dex::set_protocol_fee(Origin::root(), tka, MultiAssetId::Asset(TAB), None, true),
+            Error::<Test>::PoolNotFound
+        );
+
+        assert_ok!(Dex::set_protocol_fee(Origin::root(), tka, tkb, None, true));
+        let pool = Dex::get_pool(tka, tkb, None).unwrap();
+        assert!(pool.protocol_fee_on);
+        assert_eq!(pool.root_k_last, POOL_LIQUIDITY);
+        System::assert_last_event(mock::Event::Dex(crate::Event::ProtocolFeeSet(
+            tka,
+            tkb,
+            DefaultFee::get(),
+            true,
+        )));
+    });
+}
+
+// The next liquidity event mints S * (sqrt(k) - sqrt(k_last)) / (5 * sqrt(k) + sqrt(k_last))
+// LP tokens to ProtocolFeeReceiver, 1/6 of the fee growth since k_last
+#[test]
+fn mints_protocol_fee_on_liquidity_event() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB));
+        assert_ok!(Dex::set_protocol_fee(Origin::root(), tka, tkb, None, true));
+
+        // Round trips leave the fees in the pool
+        for _ in 0..5 {
+            let before = Dex::balance(tkb, &BOB);
+            assert_ok!(Dex::swap_exact_tokens_for_tokens(Origin::signed(BOB), tka, tkb, None, 50_000, 0));
+            let received = Dex::balance(tkb, &BOB) - before;
+            assert_ok!(Dex::swap_exact_tokens_for_tokens(Origin::signed(BOB), tkb, tka, None, received, 0));
+        }
+        let pool = Dex::get_pool(tka, tkb, None).unwrap();
+        assert_eq!(pool.reserves(), (101_012, 100_000));
+        let supply = Assets::total_supply(pool.id);
+        let (root_k, root_k_last) = (100_504, POOL_LIQUIDITY);
+        let expected = supply * (root_k - root_k_last) / (5 * root_k + root_k_last);
+        assert_eq!(expected, 83);
+
+        // Swaps alone don't mint
+        assert_eq!(Assets::balance(pool.id, ProtocolFeeReceiver::get()), 0);
+        assert_ok!(Dex::add_liquidity(Origin::signed(ALICE), 10_102, tka, 10_000, tkb, None, 0, 0));
+
+        let pool = Dex::get_pool(tka, tkb, None).unwrap();
+        assert_eq!(Assets::balance(pool.id, ProtocolFeeReceiver::get()), expected);
+        assert_eq!(pool.reserves(), (111_114, 110_000));
+        assert_eq!(pool.root_k_last, 110_555); // sqrt(111_114 * 110_000)
+    });
+}
+
+// Switching the protocol fee off forgets k_last, so nothing is minted for the fees earned
+// while it was off
+#[test]
+fn protocol_fee_off_resets_root_k_last() {
+    ExtBuilder::default().with_pools().build().execute_with(|| {
+        let (tka, tkb) = (MultiAssetId::Asset(TKA), MultiAssetId::Asset(TKB));
+        assert_ok!(Dex::set_protocol_fee(Origin::root(), tka, tkb, None, true));
+        assert_ok!(Dex::set_protocol_fee(Origin::root(), tka, tkb, None, false));
+        let pool = Dex::get_pool(tka, tkb, None).unwrap();
+        assert!(!pool.protocol_fee_on);
+        assert_eq!(pool.root_k_last, 0);
+
+        assert_ok!(Dex::swap_exact_tokens_for_tokens(Origin::signed(BOB), tka, tkb, None, 50_000, 0));
+        assert_ok!(Dex::remove_liquidity(Origin::signed(ALICE), tka, tkb, None, 1_000, 0, 0));
+        assert_eq!(Assets::balance(pool.id, ProtocolFeeReceiver::get()), 0);
+        assert_eq!(Dex::get_pool(tka, tkb, None).unwrap().root_k_last, 0);
+
+        // Switching it back on starts from the current reserves
+        assert_ok!(Dex::set_protocol_fee(Origin::root(), tka, tkb, None, true));
+        let pool = Dex::get_pool(tka, tkb, None).unwrap();
+        let (reserve_0, reserve_1) = pool.reserves();
+        assert_eq!(pool.root_k_last, (reserve_0 * reserve_1).integer_sqrt());
+        assert_ok!(Dex::remove_liquidity(Origin::signed(ALICE), tka, tkb, None, 1_000, 0, 0));
+        assert_eq!(Assets::balance(pool.id, ProtocolFeeReceiver::get()), 0);
+    });
+}
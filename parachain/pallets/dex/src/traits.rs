@@ -0,0 +1,65 @@
+//! Traits for other pallets to pay and swap through the DEX.
+
+use crate::{pallet::{BalanceOf, MultiAssetIdOf}, Config, Error, Pallet};
+use codec::MaxEncodedLen;
+use frame_support::{ensure, pallet_prelude::{DispatchError, DispatchResult}, Parameter};
+use frame_support::sp_std::vec;
+
+/// Move and swap the assets of the DEX on behalf of an account
+pub trait Swap<AccountId, Balance> {
+	/// The assets which can be moved and swapped
+	type AssetId: Parameter + Copy + MaxEncodedLen;
+
+	/// Get the free balance of an asset
+	fn balance(asset: Self::AssetId, who: &AccountId) -> Balance;
+
+	/// Move an asset between two accounts
+	fn transfer(asset: Self::AssetId, from: &AccountId, to: &AccountId, amount: Balance) -> DispatchResult;
+
+	/// Swap as little `asset_in` as needed for `who` to receive exactly `amount_out` of
+	/// `asset_out`, failing if that costs more than `amount_in_max`. Returns the amount of
+	/// `asset_in` paid.
+	fn swap_tokens_for_exact_tokens(
+		who: &AccountId,
+		asset_in: Self::AssetId,
+		asset_out: Self::AssetId,
+		amount_out: Balance,
+		amount_in_max: Balance,
+	) -> Result<Balance, DispatchError>;
+}
+
+impl<T: Config> Swap<T::AccountId, BalanceOf<T>> for Pallet<T> {
+	type AssetId = MultiAssetIdOf<T>;
+
+	fn balance(asset: Self::AssetId, who: &T::AccountId) -> BalanceOf<T> {
+		Pallet::<T>::balance(asset, who)
+	}
+
+	fn transfer(
+		asset: Self::AssetId,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: BalanceOf<T>,
+	) -> DispatchResult {
+		Pallet::<T>::transfer(asset, from, to, amount)
+	}
+
+	/// Swaps through the `DefaultFee` pool of the two assets
+	fn swap_tokens_for_exact_tokens(
+		who: &T::AccountId,
+		asset_in: Self::AssetId,
+		asset_out: Self::AssetId,
+		amount_out: BalanceOf<T>,
+		amount_in_max: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		ensure!(amount_out > <BalanceOf<T>>::default(), Error::<T>::AmountZero);
+
+		let path = vec![asset_in, asset_out];
+		let amounts = Pallet::<T>::get_amounts_in(amount_out, &path, None)?;
+		ensure!(amounts[0] <= amount_in_max, Error::<T>::ExcessiveInputAmount);
+		ensure!(Pallet::<T>::balance(asset_in, who) >= amounts[0], Error::<T>::InsufficientBalance);
+
+		Pallet::<T>::do_swap_along_path(who, &path, None, &amounts)?;
+		Ok(amounts[0])
+	}
+}
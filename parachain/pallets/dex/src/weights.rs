@@ -0,0 +1,186 @@
+//! Placeholder weights for pallet_dex
+//!
+//! These weights were NOT produced by benchmarking: the base weights and component slopes are
+//! hand estimates and the storage weights follow the reads and writes listed above each function.
+//! `n` is 1 for a pool of the native currency and `h` is the number of pools a path swap goes
+//! through. Replace this file on reference hardware with the output of
+//!
+//! ```text
+//! ./target/release/parachain-node benchmark pallet --chain=dev --execution=wasm \
+//!     --wasm-execution=compiled --pallet=pallet_dex --extrinsic='*' --steps=50 --repeat=20 \
+//!     --template=.maintain/frame-weight-template.hbs --output=pallets/dex/src/weights.rs
+//! ```
+//!
+//! from a node built with `--features runtime-benchmarks`.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use frame_support::sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_dex.
+pub trait WeightInfo {
+	fn create_pool(n: u32, ) -> Weight;
+	fn add_liquidity(n: u32, ) -> Weight;
+	fn remove_liquidity(n: u32, ) -> Weight;
+	fn swap_exact_tokens_for_tokens(n: u32, ) -> Weight;
+	fn swap_tokens_for_exact_tokens(n: u32, ) -> Weight;
+	fn swap_exact_in_along_path(h: u32, ) -> Weight;
+	fn swap_exact_out_along_path(h: u32, ) -> Weight;
+	fn set_protocol_fee() -> Weight;
+}
+
+/// Placeholder weights for pallet_dex, see the module documentation.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: Dex LiquidityPools (r:1 w:1)
+	// Storage: Dex GetLpTokenId (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Metadata (r:3 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: System Account (r:1 w:1), the pool account if n is 1
+	fn create_pool(n: u32, ) -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add((20_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: Dex LiquidityPools (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Dex Observations (r:1 w:1)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn add_liquidity(n: u32, ) -> Weight {
+		(96_000_000 as Weight)
+			.saturating_add((10_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(11 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+	}
+	// Storage: Dex LiquidityPools (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Dex Observations (r:1 w:1)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:5 w:5)
+	fn remove_liquidity(n: u32, ) -> Weight {
+		(94_000_000 as Weight)
+			.saturating_add((10_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(11 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+	}
+	// Storage: Dex LiquidityPools (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Dex Observations (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	// Storage: Dex LastTrades (r:0 w:1)
+	fn swap_exact_tokens_for_tokens(n: u32, ) -> Weight {
+		(64_000_000 as Weight)
+			.saturating_add((10_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	// Storage: Dex LiquidityPools (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Dex Observations (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:4 w:4)
+	// Storage: Dex LastTrades (r:0 w:1)
+	fn swap_tokens_for_exact_tokens(n: u32, ) -> Weight {
+		(65_000_000 as Weight)
+			.saturating_add((10_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Dex LiquidityPools (r:1 w:1)
+	// Storage: Dex Observations (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Dex LastTrades (r:0 w:1)
+	fn swap_exact_in_along_path(h: u32, ) -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add((52_000_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(h as Weight)))
+			.saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(h as Weight)))
+	}
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Dex LiquidityPools (r:1 w:1)
+	// Storage: Dex Observations (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Dex LastTrades (r:0 w:1)
+	fn swap_exact_out_along_path(h: u32, ) -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add((53_000_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(h as Weight)))
+			.saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(h as Weight)))
+	}
+	// Storage: Dex LiquidityPools (r:1 w:1)
+	// Storage: Assets Account (r:2 w:0)
+	fn set_protocol_fee() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn create_pool(n: u32, ) -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add((20_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	fn add_liquidity(n: u32, ) -> Weight {
+		(96_000_000 as Weight)
+			.saturating_add((10_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(11 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+	}
+	fn remove_liquidity(n: u32, ) -> Weight {
+		(94_000_000 as Weight)
+			.saturating_add((10_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(11 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+	}
+	fn swap_exact_tokens_for_tokens(n: u32, ) -> Weight {
+		(64_000_000 as Weight)
+			.saturating_add((10_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+	}
+	fn swap_tokens_for_exact_tokens(n: u32, ) -> Weight {
+		(65_000_000 as Weight)
+			.saturating_add((10_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+	}
+	fn swap_exact_in_along_path(h: u32, ) -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add((52_000_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(h as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(h as Weight)))
+	}
+	fn swap_exact_out_along_path(h: u32, ) -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add((53_000_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(h as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(h as Weight)))
+	}
+	fn set_protocol_fee() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+}
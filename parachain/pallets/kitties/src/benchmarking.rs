@@ -0,0 +1,192 @@
+//! Benchmarking setup for pallet-kitties
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Kitties;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{
+	sp_std::vec::Vec,
+	traits::{Currency, Get, Hooks},
+};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Saturating;
+
+/// Sets up what the benchmarks can't do through `Config::Dex`
+pub trait BenchmarkHelper<AccountId, AssetId, Balance> {
+	/// Returns an asset to list kitties in and a different asset to pay for them with, funding
+	/// `who` to pay `price` in either, the second one by swapping it through the DEX
+	fn setup_market(who: &AccountId, price: Balance) -> (AssetId, AssetId);
+}
+
+const SEED: u32 = 0;
+
+/// The blocks every benchmarked auction runs for
+const DURATION: u32 = 10;
+
+/// The balance every account is funded with
+fn funds<T: Config>() -> BalanceOf<T> {
+	T::Currency::minimum_balance().saturating_mul(1_000_000u32.into())
+}
+
+/// The price kitties are sold and bid for
+fn price<T: Config>() -> BalanceOf<T> {
+	T::Currency::minimum_balance().saturating_mul(1_000u32.into())
+}
+
+/// An account other than the caller, funded with `funds`
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&who, funds::<T>());
+	who
+}
+
+/// A DNA unique to the `index`th kitty minted by the benchmarks for `role`
+fn dna(role: u8, index: u32) -> [u8; 16] {
+	let mut dna = [role; 16];
+	dna[1..5].copy_from_slice(&index.to_le_bytes());
+	dna
+}
+
+/// Mint `n` kitties of alternating genders for `owner`, males first
+fn mint_kitties<T: Config>(owner: &T::AccountId, role: u8, n: u32) -> Vec<[u8; 16]> {
+	(0..n)
+		.map(|i| {
+			let gender = if i % 2 == 0 { Gender::Male } else { Gender::Female };
+			Kitties::<T>::mint(owner, dna(role, i), gender).unwrap()
+		})
+		.collect()
+}
+
+/// Mint as many kitties for `owner` as leave room for one more, the longest owned list a call
+/// can push to
+fn fill_owned<T: Config>(owner: &T::AccountId, role: u8) -> Vec<[u8; 16]> {
+	mint_kitties::<T>(owner, role, T::MaxKittiesOwned::get() - 1)
+}
+
+/// Mint a kitty for the `index`th seller and auction it for `DURATION` blocks
+fn create_auction<T: Config>(index: u32, kind: AuctionKind<BalanceOf<T>>) -> [u8; 16] {
+	let seller = funded_account::<T>("seller", index);
+	let kitty = Kitties::<T>::mint(&seller, dna(1, index), Gender::Female).unwrap();
+	Kitties::<T>::create_auction(RawOrigin::Signed(seller).into(), kitty, kind, DURATION.into())
+		.unwrap();
+	kitty
+}
+
+/// Fill the auctions ending with the benchmarked one but for one, the longest list an auction
+/// can be added to or removed from
+fn fill_ending_auctions<T: Config>() {
+	let kind = AuctionKind::English { min_bid: price::<T>() };
+	for index in 1..T::MaxAuctionsPerBlock::get() {
+		create_auction::<T>(index, kind);
+	}
+}
+
+benchmarks! {
+	create_kitty {
+		let caller: T::AccountId = whitelisted_caller();
+		fill_owned::<T>(&caller, 0);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(KittiesOwned::<T>::get(&caller).len() as u32, T::MaxKittiesOwned::get());
+	}
+
+	transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		let kitty = fill_owned::<T>(&caller, 0)[0];
+		let to = funded_account::<T>("to", 0);
+		fill_owned::<T>(&to, 2);
+	}: _(RawOrigin::Signed(caller), to.clone(), kitty)
+	verify {
+		assert_eq!(pallet::Kitties::<T>::get(kitty).unwrap().owner, to);
+	}
+
+	set_price {
+		let caller: T::AccountId = whitelisted_caller();
+		let kitty = mint_kitties::<T>(&caller, 0, 1)[0];
+		let (asset, _) = T::BenchmarkHelper::setup_market(&caller, price::<T>());
+		let new_price = Some((asset, price::<T>()));
+	}: _(RawOrigin::Signed(caller), kitty, new_price)
+	verify {
+		assert_eq!(pallet::Kitties::<T>::get(kitty).unwrap().price, new_price);
+	}
+
+	breed_kitty {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, funds::<T>());
+		let parents = fill_owned::<T>(&caller, 0);
+	}: _(RawOrigin::Signed(caller.clone()), parents[0], parents[1])
+	verify {
+		assert_eq!(KittiesOwned::<T>::get(&caller).len() as u32, T::MaxKittiesOwned::get());
+	}
+
+	create_auction {
+		let caller: T::AccountId = whitelisted_caller();
+		let kitty = mint_kitties::<T>(&caller, 0, 1)[0];
+		fill_ending_auctions::<T>();
+		let kind = AuctionKind::English { min_bid: price::<T>() };
+	}: _(RawOrigin::Signed(caller), kitty, kind, DURATION.into())
+	verify {
+		assert!(Auctions::<T>::contains_key(kitty));
+	}
+
+	// A bid in a Dutch auction, which sells the kitty right away
+	bid {
+		let kind = AuctionKind::Dutch {
+			start_price: price::<T>().saturating_mul(2u32.into()),
+			floor_price: price::<T>(),
+		};
+		let kitty = create_auction::<T>(0, kind);
+		fill_ending_auctions::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, funds::<T>());
+		fill_owned::<T>(&caller, 0);
+		let amount = price::<T>().saturating_mul(2u32.into());
+	}: _(RawOrigin::Signed(caller.clone()), kitty, amount)
+	verify {
+		assert_eq!(pallet::Kitties::<T>::get(kitty).unwrap().owner, caller);
+		assert!(!Auctions::<T>::contains_key(kitty));
+	}
+
+	// `s` is 1 when the kitty is paid in another asset, swapped through the DEX
+	buy_kitty {
+		let s in 0 .. 1;
+		let seller = funded_account::<T>("seller", 0);
+		let kitty = mint_kitties::<T>(&seller, 1, 1)[0];
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, funds::<T>());
+		fill_owned::<T>(&caller, 0);
+		let (asset, pay_asset) = T::BenchmarkHelper::setup_market(&caller, price::<T>());
+		let new_price = Some((asset, price::<T>()));
+		Kitties::<T>::set_price(RawOrigin::Signed(seller).into(), kitty, new_price).unwrap();
+		let pay_with = if s == 1 { Some((pay_asset, funds::<T>())) } else { None };
+	}: _(RawOrigin::Signed(caller.clone()), kitty, price::<T>(), pay_with)
+	verify {
+		assert_eq!(pallet::Kitties::<T>::get(kitty).unwrap().owner, caller);
+	}
+
+	// Settling `a` English auctions ending in the same block, every one sold to its bidder
+	on_initialize {
+		let a in 0 .. T::MaxAuctionsPerBlock::get();
+		let kind = AuctionKind::English { min_bid: price::<T>() };
+		let mut sales = Vec::new();
+		for index in 0..a {
+			let kitty = create_auction::<T>(index, kind);
+			let bidder = funded_account::<T>("bidder", index);
+			let origin = RawOrigin::Signed(bidder.clone());
+			Kitties::<T>::bid(origin.into(), kitty, price::<T>()).unwrap();
+			sales.push((kitty, bidder));
+		}
+		let end = frame_system::Pallet::<T>::block_number().saturating_add(DURATION.into());
+	}: {
+		Kitties::<T>::on_initialize(end);
+	}
+	verify {
+		for (kitty, bidder) in sales {
+			assert_eq!(pallet::Kitties::<T>::get(kitty).unwrap().owner, bidder);
+		}
+		assert!(AuctionsEndingAt::<T>::get(end).is_empty());
+	}
+
+	impl_benchmark_test_suite!(Kitties, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -2,29 +2,64 @@
 
 pub use pallet::*;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(feature = "runtime-benchmarks")]
+pub use benchmarking::BenchmarkHelper;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::pallet_prelude::*;
     use frame_system::pallet_prelude::*;
 
-    use frame_support::traits::{Currency, Randomness};
+    use frame_support::storage::with_storage_layer;
+    use frame_support::traits::{
+        BalanceStatus, Currency, ExistenceRequirement, OnUnbalanced, Randomness, ReservableCurrency,
+        WithdrawReasons,
+    };
+    use sp_runtime::traits::{Saturating, Zero};
+    use sp_runtime::{Perbill, SaturatedConversion};
+    use frame_support::sp_std::vec::Vec;
+    use pallet_dex::Swap;
+    use crate::WeightInfo;
+    #[cfg(feature = "std")]
+    use serde::{Deserialize, Serialize};
 
     // The basis which we buil
     #[pallet::pallet]
     pub struct Pallet<T>(_);
 
     // Allows easy access our Pallet's `Balance` type. Comes from `Currency` interface.
-    type BalanceOf<T> =
+    pub type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+    /// The assets kitties can be priced in, e.g. the native currency or a DEX token
+    pub type AssetIdOf<T> = <<T as Config>::Dex as Swap<
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T>,
+    >>::AssetId;
+
+    type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+        <T as frame_system::Config>::AccountId,
+    >>::NegativeImbalance;
+
     // Your Pallet's configuration trait, representing custom external types and interfaces.
     #[pallet::config]
     pub trait Config: frame_system::Config {
         /// Because this pallet emits events, it depends on the runtime's definition of an event.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
-        /// The Currency handler for the kitties pallet.
-        type Currency: Currency<Self::AccountId>;
+        /// The Currency handler for the kitties pallet, bids are reserved while an auction runs.
+        type Currency: ReservableCurrency<Self::AccountId>;
 
         /// The maximum amount of kitties a single account can own.
         #[pallet::constant]
@@ -32,10 +67,41 @@ pub mod pallet {
 
         /// The type of Randomness we want to specify for this pallet.
         type KittyRandomness: Randomness<Self::Hash, Self::BlockNumber>;
+
+        /// The number of blocks a kitty has to wait before it can breed again.
+        #[pallet::constant]
+        type BreedingCooldown: Get<Self::BlockNumber>;
+
+        /// The fee charged for breeding, zero for free breeding.
+        #[pallet::constant]
+        type BreedingFee: Get<BalanceOf<Self>>;
+
+        /// Handler for the breeding fee, e.g. a treasury. Dropping it burns the fee.
+        type OnBreedingFee: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+        /// The maximum amount of auctions ending in the same block.
+        #[pallet::constant]
+        type MaxAuctionsPerBlock: Get<u32>;
+
+        /// The DEX used to pay for kitties listed in any asset, swapping the buyer's asset into
+        /// the listing asset when needed.
+        type Dex: Swap<Self::AccountId, BalanceOf<Self>>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+
+        /// Sets up the DEX markets of the benchmarks.
+        #[cfg(feature = "runtime-benchmarks")]
+        type BenchmarkHelper: crate::BenchmarkHelper<
+            Self::AccountId,
+            AssetIdOf<Self>,
+            BalanceOf<Self>,
+        >;
     }
 
     // The Gender type used in the `Kitty` struct
     #[derive(Clone, Encode, Decode, PartialEq, Copy, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
     pub enum Gender {
         Male,
         Female,
@@ -47,12 +113,33 @@ pub mod pallet {
     pub struct Kitty<T: Config> {
         // Using 16 bytes to represent a kitty DNA
         pub dna: [u8; 16],
-        // `None` assumes not for sale
-        pub price: Option<BalanceOf<T>>,
+        // The asset and amount asked, `None` assumes not for sale
+        pub price: Option<(AssetIdOf<T>, BalanceOf<T>)>,
         pub gender: Gender,
         pub owner: T::AccountId,
     }
 
+    // The kinds of auction a kitty can be sold in
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, Copy, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum AuctionKind<Balance> {
+        /// Ascending bids, the highest bid wins when the auction ends
+        English { min_bid: Balance },
+        /// The price falls linearly from `start_price` to `floor_price`, the first bid wins
+        Dutch { start_price: Balance, floor_price: Balance },
+    }
+
+    // Struct for holding auction information
+    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct Auction<T: Config> {
+        pub seller: T::AccountId,
+        pub kind: AuctionKind<BalanceOf<T>>,
+        pub start: T::BlockNumber,
+        pub end: T::BlockNumber,
+        // The highest bid so far, reserved from the bidder
+        pub best_bid: Option<(T::AccountId, BalanceOf<T>)>,
+    }
+
     /// Keeps track of the number of kitties in existence.
     #[pallet::storage]
     pub(super) type CountForKitties<T: Config> = StorageValue<
@@ -80,6 +167,59 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// The block from which a kitty can breed again.
+    #[pallet::storage]
+    pub(super) type NextBreedingAt<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        [u8; 16],
+        T::BlockNumber,
+        ValueQuery,
+    >;
+
+    /// Running auctions by kitty.
+    #[pallet::storage]
+    pub(super) type Auctions<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        [u8; 16],
+        Auction<T>,
+    >;
+
+    /// The kitties whose auction is settled at the start of a block.
+    #[pallet::storage]
+    pub(super) type AuctionsEndingAt<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        BoundedVec<[u8; 16], T::MaxAuctionsPerBlock>,
+        ValueQuery,
+    >;
+
+    // Kitties minted at genesis
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config> {
+        /// Genesis kitties: (owner, dna, gender)
+        pub kitties: Vec<(T::AccountId, [u8; 16], Gender)>,
+    }
+
+    #[cfg(feature = "std")]
+    impl<T: Config> Default for GenesisConfig<T> {
+        fn default() -> Self {
+            Self { kitties: Default::default() }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+        fn build(&self) {
+            for (owner, dna, gender) in &self.kitties {
+                Pallet::<T>::mint(owner, *dna, *gender)
+                    .expect("Should be able to mint kitties during genesis");
+            }
+        }
+    }
+
     // Your Pallet's events.
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -91,10 +231,22 @@ pub mod pallet {
         Transferred { from: T::AccountId, to: T::AccountId, kitty: [u8;16] },
 
         /// The price was successfully set
-        PriceSet { kitty: [u8; 16], price: Option<BalanceOf<T>> },
+        PriceSet { kitty: [u8; 16], price: Option<(AssetIdOf<T>, BalanceOf<T>)> },
 
         /// Sold event
-        Sold { seller: T::AccountId, buyer: T::AccountId, kitty: [u8; 16], price: BalanceOf<T> },
+        Sold { seller: T::AccountId, buyer: T::AccountId, kitty: [u8; 16], asset: AssetIdOf<T>, price: BalanceOf<T> },
+
+        /// A new kitty was bred from two parents
+        Bred { kitty: [u8; 16], parents: ([u8; 16], [u8; 16]), owner: T::AccountId },
+
+        /// An auction was started
+        AuctionCreated { kitty: [u8; 16], seller: T::AccountId, kind: AuctionKind<BalanceOf<T>>, end: T::BlockNumber },
+
+        /// A bid was placed in an auction
+        BidPlaced { kitty: [u8; 16], bidder: T::AccountId, amount: BalanceOf<T> },
+
+        /// An auction ended, `winner` is `None` when the kitty wasn't sold
+        AuctionSettled { kitty: [u8; 16], winner: Option<T::AccountId>, price: Option<BalanceOf<T>> },
     }
 
     // Your Pallet's error messages.
@@ -116,6 +268,34 @@ pub mod pallet {
         BidPriceTooLow,
         /// This kitty is not for sale.
         NotForSale,
+        /// Breeding needs one male and one female kitty.
+        SameGender,
+        /// The kitty bred too recently.
+        BreedingCooldown,
+        /// The kitty is being auctioned.
+        KittyInAuction,
+        /// The kitty is not being auctioned.
+        NoAuction,
+        /// The auction has ended.
+        AuctionEnded,
+        /// The auction needs a duration and a start price of at least its floor price.
+        InvalidAuction,
+        /// Too many auctions end in the same block.
+        TooManyAuctions,
+        /// The reserved bid no longer covers the price.
+        BidNotCovered,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        // Settle the auctions ending in this block
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let ending = AuctionsEndingAt::<T>::take(now);
+            for kitty_id in ending.iter() {
+                Self::settle_auction(*kitty_id);
+            }
+            T::WeightInfo::on_initialize(ending.len() as u32)
+        }
     }
 
     // Your Pallet's callable functions.
@@ -124,7 +304,7 @@ pub mod pallet {
         /// Create a new unique kitty.
         ///
         /// The actual kitty creation is done in the `mint()` function.
-        #[pallet::weight(0)]
+        #[pallet::weight(T::WeightInfo::create_kitty())]
         pub fn create_kitty(origin: OriginFor<T>) -> DispatchResult {
             // Make sure the caller is from a signed origin
             let sender = ensure_signed(origin)?;
@@ -142,7 +322,7 @@ pub mod pallet {
         /// 
         /// Any account with a kitty can send it to another Account. 
         /// Note that this will reset the asking price of the kitty, marking it not for sale.
-        #[pallet::weight(0)]
+        #[pallet::weight(T::WeightInfo::transfer())]
         pub fn transfer(
             origin: OriginFor<T>,
             to: T::AccountId,
@@ -151,24 +331,26 @@ pub mod pallet {
             let from = ensure_signed(origin)?;
             let kitty = Kitties::<T>::get(&kitty_id).ok_or(Error::<T>::NoKitty)?;
             ensure!(kitty.owner == from, Error::<T>::NotOwner);
+            ensure!(!Auctions::<T>::contains_key(&kitty_id), Error::<T>::KittyInAuction);
             Self::do_transfer(kitty_id, to)?;
             Ok(())
         }
 
         /// Set the price
         /// 
-        /// Update kitty price and storage
-        #[pallet::weight(0)]
+        /// Update kitty price and storage, the price can be asked in any asset of the DEX
+        #[pallet::weight(T::WeightInfo::set_price())]
         pub fn set_price(
             origin: OriginFor<T>,
             kitty_id: [u8; 16],
-            new_price: Option<BalanceOf<T>>,
+            new_price: Option<(AssetIdOf<T>, BalanceOf<T>)>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
             // Make sure the owner is the sender
             let mut kitty = Kitties::<T>::get(&kitty_id).ok_or(Error::<T>::NoKitty)?;
             ensure!(kitty.owner == sender, Error::<T>::NotOwner);
+            ensure!(!Auctions::<T>::contains_key(&kitty_id), Error::<T>::KittyInAuction);
 
             // Set the price in storage
             kitty.price = new_price;
@@ -179,6 +361,163 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Breed a new kitty from a male and a female kitty owned by the caller
+        ///
+        /// The child DNA takes every bit from one of the parents at random. Both parents have to
+        /// wait `BreedingCooldown` blocks before breeding again.
+        #[pallet::weight(T::WeightInfo::breed_kitty())]
+        pub fn breed_kitty(
+            origin: OriginFor<T>,
+            parent_1: [u8; 16],
+            parent_2: [u8; 16],
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let kitty_1 = Kitties::<T>::get(&parent_1).ok_or(Error::<T>::NoKitty)?;
+            let kitty_2 = Kitties::<T>::get(&parent_2).ok_or(Error::<T>::NoKitty)?;
+            ensure!(kitty_1.owner == sender && kitty_2.owner == sender, Error::<T>::NotOwner);
+            ensure!(kitty_1.gender != kitty_2.gender, Error::<T>::SameGender);
+
+            // Both parents must be rested
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                NextBreedingAt::<T>::get(&parent_1) <= now && NextBreedingAt::<T>::get(&parent_2) <= now,
+                Error::<T>::BreedingCooldown
+            );
+
+            // Charge the breeding fee
+            let fee = T::BreedingFee::get();
+            if !fee.is_zero() {
+                let imbalance = T::Currency::withdraw(
+                    &sender,
+                    fee,
+                    WithdrawReasons::FEE,
+                    ExistenceRequirement::KeepAlive,
+                )?;
+                T::OnBreedingFee::on_unbalanced(imbalance);
+            }
+
+            let (dna, gender) = Self::breed_dna(&kitty_1.dna, &kitty_2.dna);
+            Self::mint(&sender, dna, gender)?;
+
+            let next_breeding_at = now.saturating_add(T::BreedingCooldown::get());
+            NextBreedingAt::<T>::insert(&parent_1, next_breeding_at);
+            NextBreedingAt::<T>::insert(&parent_2, next_breeding_at);
+
+            Self::deposit_event(Event::Bred { kitty: dna, parents: (parent_1, parent_2), owner: sender });
+
+            Ok(())
+        }
+
+        /// Start an auction of a kitty for `duration` blocks
+        ///
+        /// The kitty is taken off fixed-price sale until the auction is settled.
+        #[pallet::weight(T::WeightInfo::create_auction())]
+        pub fn create_auction(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 16],
+            kind: AuctionKind<BalanceOf<T>>,
+            duration: T::BlockNumber,
+        ) -> DispatchResult {
+            let seller = ensure_signed(origin)?;
+
+            let mut kitty = Kitties::<T>::get(&kitty_id).ok_or(Error::<T>::NoKitty)?;
+            ensure!(kitty.owner == seller, Error::<T>::NotOwner);
+            ensure!(!Auctions::<T>::contains_key(&kitty_id), Error::<T>::KittyInAuction);
+            ensure!(!duration.is_zero(), Error::<T>::InvalidAuction);
+            if let AuctionKind::Dutch { start_price, floor_price } = kind {
+                ensure!(start_price >= floor_price, Error::<T>::InvalidAuction);
+            }
+
+            let start = frame_system::Pallet::<T>::block_number();
+            let end = start.saturating_add(duration);
+            AuctionsEndingAt::<T>::try_append(end, kitty_id)
+                .map_err(|_| Error::<T>::TooManyAuctions)?;
+            Auctions::<T>::insert(&kitty_id, Auction::<T> { seller: seller.clone(), kind, start, end, best_bid: None });
+
+            kitty.price = None;
+            Kitties::<T>::insert(&kitty_id, kitty);
+
+            Self::deposit_event(Event::AuctionCreated { kitty: kitty_id, seller, kind, end });
+
+            Ok(())
+        }
+
+        /// Bid in the auction of a kitty
+        ///
+        /// The bid is reserved until it is outbid or the auction is settled. The first bid which
+        /// covers the current price of a Dutch auction buys the kitty at that price right away,
+        /// and fails if the kitty can't be transferred to the bidder.
+        #[pallet::weight(T::WeightInfo::bid())]
+        pub fn bid(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 16],
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let bidder = ensure_signed(origin)?;
+
+            let mut auction = Auctions::<T>::get(&kitty_id).ok_or(Error::<T>::NoAuction)?;
+            ensure!(auction.seller != bidder, Error::<T>::TransferToSelf);
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(now < auction.end, Error::<T>::AuctionEnded);
+
+            match auction.kind {
+                AuctionKind::English { min_bid } => {
+                    ensure!(amount >= min_bid, Error::<T>::BidPriceTooLow);
+                    if let Some((_, best)) = &auction.best_bid {
+                        ensure!(amount > *best, Error::<T>::BidPriceTooLow);
+                    }
+
+                    // Reserve the new bid and refund the outbid one
+                    T::Currency::reserve(&bidder, amount)?;
+                    if let Some((outbid, best)) = auction.best_bid.take() {
+                        T::Currency::unreserve(&outbid, best);
+                    }
+                    auction.best_bid = Some((bidder.clone(), amount));
+                    Auctions::<T>::insert(&kitty_id, auction);
+
+                    Self::deposit_event(Event::BidPlaced { kitty: kitty_id, bidder, amount });
+                },
+                AuctionKind::Dutch { .. } => {
+                    let price = Self::dutch_price(&auction, now);
+                    ensure!(amount >= price, Error::<T>::BidPriceTooLow);
+
+                    // Sell right away, the call is reverted if either transfer fails
+                    Self::do_transfer(kitty_id, bidder.clone())?;
+                    T::Currency::transfer(&bidder, &auction.seller, price, ExistenceRequirement::KeepAlive)?;
+                    Auctions::<T>::remove(&kitty_id);
+                    AuctionsEndingAt::<T>::mutate(auction.end, |ending| ending.retain(|id| *id != kitty_id));
+
+                    Self::deposit_event(Event::BidPlaced { kitty: kitty_id, bidder: bidder.clone(), amount: price });
+                    Self::deposit_event(Event::AuctionSettled {
+                        kitty: kitty_id,
+                        winner: Some(bidder),
+                        price: Some(price),
+                    });
+                },
+            }
+
+            Ok(())
+        }
+
+        /// Buy a kitty which is for sale
+        ///
+        /// `bid_price` is the most the buyer is willing to pay in the listing asset, only the
+        /// asking price is charged. With `pay_with` set to `(asset, max_amount_in)` the buyer
+        /// pays in `asset` instead, which is swapped into the asking price through the DEX for
+        /// at most `max_amount_in`.
+        #[pallet::weight(T::WeightInfo::buy_kitty(pay_with.is_some() as u32))]
+        pub fn buy_kitty(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 16],
+            bid_price: BalanceOf<T>,
+            pay_with: Option<(AssetIdOf<T>, BalanceOf<T>)>,
+        ) -> DispatchResult {
+            let buyer = ensure_signed(origin)?;
+            Self::do_buy_kitty(kitty_id, buyer, bid_price, pay_with)?;
+            Ok(())
+        }
     }
 
     // Your Pallet's internal functions.
@@ -208,6 +547,75 @@ pub mod pallet {
             }
         }
 
+        // The current price of a Dutch auction, falling linearly from the start to the floor
+        // price over the auction
+        fn dutch_price(auction: &Auction<T>, now: T::BlockNumber) -> BalanceOf<T> {
+            match auction.kind {
+                AuctionKind::Dutch { start_price, floor_price } => {
+                    let elapsed = now.saturating_sub(auction.start).saturated_into::<u32>();
+                    let duration = auction.end.saturating_sub(auction.start).saturated_into::<u32>();
+                    let discount = Perbill::from_rational(elapsed, duration) * (start_price - floor_price);
+                    start_price - discount
+                },
+                AuctionKind::English { min_bid } => min_bid,
+            }
+        }
+
+        // Ends an English auction, moving the kitty to the best bidder through `do_transfer` and
+        // the reserved bid to the seller. Both happen or neither does: without a bid, if the
+        // kitty can't be transferred or if the reserved bid doesn't pay the whole price (it may
+        // have been slashed), the kitty stays with the seller and what is left of the bid is
+        // refunded.
+        fn settle_auction(kitty_id: [u8; 16]) {
+            let auction = match Auctions::<T>::take(&kitty_id) {
+                Some(auction) => auction,
+                None => return,
+            };
+
+            let (winner, price) = match auction.best_bid {
+                Some((bidder, amount)) => {
+                    let sold = with_storage_layer(|| -> DispatchResult {
+                        Self::do_transfer(kitty_id, bidder.clone())?;
+                        let unpaid = T::Currency::repatriate_reserved(
+                            &bidder,
+                            &auction.seller,
+                            amount,
+                            BalanceStatus::Free,
+                        )?;
+                        ensure!(unpaid.is_zero(), Error::<T>::BidNotCovered);
+                        Ok(())
+                    });
+                    match sold {
+                        Ok(()) => (Some(bidder), Some(amount)),
+                        Err(_) => {
+                            T::Currency::unreserve(&bidder, amount);
+                            (None, None)
+                        },
+                    }
+                },
+                None => (None, None),
+            };
+
+            Self::deposit_event(Event::AuctionSettled { kitty: kitty_id, winner, price });
+        }
+
+        // Mixes the DNA of two parents and returns the child DNA and Gender
+        fn breed_dna(dna_1: &[u8; 16], dna_2: &[u8; 16]) -> ([u8; 16], Gender) {
+            // Every bit set in the selector is taken from the first parent
+            let (selector, _) = Self::gen_dna();
+
+            let mut dna = [0u8; 16];
+            for (i, byte) in dna.iter_mut().enumerate() {
+                *byte = (dna_1[i] & selector[i]) | (dna_2[i] & !selector[i]);
+            }
+
+            if dna[0] % 2 == 0 {
+                (dna, Gender::Male)
+            } else {
+                (dna, Gender::Female)
+            }
+        }
+
         // Helper to mint a kitty
         pub fn mint(
             owner: &T::AccountId,
@@ -280,11 +688,18 @@ pub mod pallet {
             kitty_id: [u8; 16],
             to: T::AccountId,
             bid_price: BalanceOf<T>,
+            pay_with: Option<(AssetIdOf<T>, BalanceOf<T>)>,
         ) -> DispatchResult {
             let mut kitty = Kitties::<T>::get(&kitty_id).ok_or(Error::<T>::NoKitty)?;
             let from = kitty.owner;
 
             ensure!(from != to, Error::<T>::TransferToSelf);
+            ensure!(!Auctions::<T>::contains_key(&kitty_id), Error::<T>::KittyInAuction);
+
+            // Check the price before touching the owner lists
+            let (asset, price) = kitty.price.ok_or(Error::<T>::NotForSale)?;
+            ensure!(bid_price >= price, Error::<T>::BidPriceTooLow);
+
             let mut from_owned = KittiesOwned::<T>::get(&from);
 
             if let Some(ind) = from_owned.iter().position(|&id| id == kitty_id) {
@@ -296,19 +711,21 @@ pub mod pallet {
             let mut to_owned = KittiesOwned::<T>::get(&to);
             to_owned.try_push(kitty_id).map_err(|()| Error::<T>::TooManyOwned)?;
 
-            if let Some(price) = kitty.price {
-                ensure!(bid_price >= price, Error::<T>::BidPriceTooLow);
-                T::Currency::transfer(&to, &from, price, frame_support::traits::ExistenceRequirement::KeepAlive)?;
-                // Deposit sold event
-                Self::deposit_event(Event::Sold {
-                    seller: from.clone(),
-                    buyer: to.clone(),
-                    kitty: kitty_id,
-                    price,
-                });
-            } else {
-                return Err(Error::<T>::NotForSale.into())
+            // Swap the buyer's asset into the asking price first
+            if let Some((asset_in, max_amount_in)) = pay_with {
+                if asset_in != asset {
+                    T::Dex::swap_tokens_for_exact_tokens(&to, asset_in, asset, price, max_amount_in)?;
+                }
             }
+            T::Dex::transfer(asset, &to, &from, price)?;
+            // Deposit sold event
+            Self::deposit_event(Event::Sold {
+                seller: from.clone(),
+                buyer: to.clone(),
+                kitty: kitty_id,
+                asset,
+                price,
+            });
 
             // Transfer succeeded
             kitty.owner = to.clone();
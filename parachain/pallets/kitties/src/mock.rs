@@ -0,0 +1,205 @@
+use crate as pallet_kitties;
+use frame_support::{parameter_types, traits::Randomness};
+
+use frame_support::traits::{
+	ConstU16, ConstU32, ConstU64, ConstU128, Currency, ExistenceRequirement, WithdrawReasons,
+};
+use pallet_dex::Swap;
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage, DispatchError, DispatchResult,
+};
+use std::{cell::RefCell, collections::BTreeMap};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Kitties: pallet_kitties::{Pallet, Call, Storage, Config<T>, Event<T>},
+	}
+);
+
+pub type AccountId = u128;
+pub type Balance = u128;
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = Balance;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU128<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+/// Deterministic randomness derived from the subject and the block number
+pub struct TestRandomness;
+
+impl Randomness<H256, u64> for TestRandomness {
+	fn random(subject: &[u8]) -> (H256, u64) {
+		let block_number = System::block_number();
+		let seed = (subject, block_number);
+		(H256::from(sp_io::hashing::blake2_256(&codec::Encode::encode(&seed))), block_number)
+	}
+}
+
+/// The asset id of the native currency in `MockDex`, other ids are plain tokens
+pub const NATIVE: u32 = 0;
+
+thread_local! {
+	static TOKENS: RefCell<BTreeMap<(u32, AccountId), Balance>> = RefCell::new(BTreeMap::new());
+}
+
+/// A DEX paying the native currency through `Balances`, and swapping at a fixed rate of two
+/// input assets per output asset
+pub struct MockDex;
+
+impl MockDex {
+	pub fn mint(asset: u32, who: &AccountId, amount: Balance) {
+		if asset == NATIVE {
+			let _ = Balances::deposit_creating(who, amount);
+		} else {
+			TOKENS.with(|tokens| *tokens.borrow_mut().entry((asset, *who)).or_default() += amount);
+		}
+	}
+
+	fn burn(asset: u32, who: &AccountId, amount: Balance) -> DispatchResult {
+		if asset == NATIVE {
+			return Balances::withdraw(who, amount, WithdrawReasons::TRANSFER, ExistenceRequirement::KeepAlive)
+				.map(|_| ())
+		}
+		TOKENS.with(|tokens| {
+			let mut tokens = tokens.borrow_mut();
+			let balance = tokens.entry((asset, *who)).or_default();
+			*balance = balance.checked_sub(amount).ok_or(DispatchError::Other("InsufficientBalance"))?;
+			Ok(())
+		})
+	}
+}
+
+impl Swap<AccountId, Balance> for MockDex {
+	type AssetId = u32;
+
+	fn balance(asset: u32, who: &AccountId) -> Balance {
+		if asset == NATIVE {
+			Balances::free_balance(who)
+		} else {
+			TOKENS.with(|tokens| tokens.borrow().get(&(asset, *who)).copied().unwrap_or_default())
+		}
+	}
+
+	fn transfer(asset: u32, from: &AccountId, to: &AccountId, amount: Balance) -> DispatchResult {
+		if asset == NATIVE {
+			return <Balances as Currency<AccountId>>::transfer(from, to, amount, ExistenceRequirement::KeepAlive)
+		}
+		Self::burn(asset, from, amount)?;
+		Self::mint(asset, to, amount);
+		Ok(())
+	}
+
+	fn swap_tokens_for_exact_tokens(
+		who: &AccountId,
+		asset_in: u32,
+		asset_out: u32,
+		amount_out: Balance,
+		amount_in_max: Balance,
+	) -> Result<Balance, DispatchError> {
+		let amount_in = amount_out * 2;
+		if amount_in > amount_in_max {
+			return Err(DispatchError::Other("ExcessiveInputAmount"))
+		}
+		Self::burn(asset_in, who, amount_in)?;
+		Self::mint(asset_out, who, amount_out);
+		Ok(amount_in)
+	}
+}
+
+/// Lists kitties in the native currency and pays for them in token 1, at the fixed rate
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper<AccountId, u32, Balance> for MockDex {
+	fn setup_market(who: &AccountId, price: Balance) -> (u32, u32) {
+		Self::mint(NATIVE, who, price);
+		Self::mint(1, who, price * 2);
+		(NATIVE, 1)
+	}
+}
+
+parameter_types! {
+	pub const MaxKittiesOwned: u32 = 10;
+}
+
+impl pallet_kitties::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type MaxKittiesOwned = MaxKittiesOwned;
+	type KittyRandomness = TestRandomness;
+	type BreedingCooldown = ConstU64<10>;
+	type BreedingFee = ConstU128<10>;
+	type OnBreedingFee = ();
+	type MaxAuctionsPerBlock = ConstU32<4>;
+	type Dex = MockDex;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockDex;
+}
+
+// Build genesis storage according to the mock runtime.
+pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisConfig {
+		balances: BalancesConfig {
+			balances: vec![
+				(1, 1_000),
+				(2, 1_000),
+				(3, 1_000),
+			],
+		},
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,326 @@
+use crate::{mock::*, pallet, AuctionKind, Auctions, Error, Gender, KittiesOwned, NextBreedingAt};
+use frame_support::{assert_noop, assert_ok, traits::{Get, Hooks, ReservableCurrency}};
+use pallet_dex::Swap;
+use sp_runtime::DispatchError;
+
+const ALICE: u128 = 1; // The seller
+const BOB: u128 = 2; // The buyer
+const KITTY: [u8; 16] = [1; 16]; // The DNA of the kitty for sale
+
+// Mint a kitty for Alice and put it up for sale in the native currency
+fn list_kitty(price: u128) {
+    list_kitty_in(NATIVE, price);
+}
+
+// Mint a kitty for Alice and put it up for sale in an asset
+fn list_kitty_in(asset: u32, price: u128) {
+    assert_ok!(Kitties::mint(&ALICE, KITTY, Gender::Female));
+    assert_ok!(Kitties::set_price(Origin::signed(ALICE), KITTY, Some((asset, price))));
+}
+
+// Overpaying buys the kitty for the asking price
+#[test]
+fn buy_kitty_charges_asking_price() {
+    new_test_ext().execute_with(|| {
+        list_kitty(100);
+        assert_ok!(Kitties::buy_kitty(Origin::signed(BOB), KITTY, 150, None));
+
+        let kitty = pallet::Kitties::<Test>::get(KITTY).unwrap();
+        assert_eq!(kitty.owner, BOB);
+        assert_eq!(kitty.price, None);
+        assert_eq!(KittiesOwned::<Test>::get(ALICE).len(), 0);
+        assert_eq!(KittiesOwned::<Test>::get(BOB).into_inner(), vec![KITTY]);
+        assert_eq!(Balances::free_balance(ALICE), 1_100);
+        assert_eq!(Balances::free_balance(BOB), 900);
+    });
+}
+
+const TOKEN: u32 = 1; // A DEX token kitties can be priced in
+
+// A kitty listed in a token is paid in that token
+#[test]
+fn buy_kitty_listed_in_token() {
+    new_test_ext().execute_with(|| {
+        list_kitty_in(TOKEN, 100);
+        MockDex::mint(TOKEN, &BOB, 150);
+        assert_ok!(Kitties::buy_kitty(Origin::signed(BOB), KITTY, 100, None));
+
+        assert_eq!(pallet::Kitties::<Test>::get(KITTY).unwrap().owner, BOB);
+        assert_eq!(MockDex::balance(TOKEN, &ALICE), 100);
+        assert_eq!(MockDex::balance(TOKEN, &BOB), 50);
+        assert_eq!(Balances::free_balance(BOB), 1_000);
+    });
+}
+
+// The buyer's asset is swapped into the listing asset, within the slippage bound
+#[test]
+fn buy_kitty_swaps_into_listing_asset() {
+    new_test_ext().execute_with(|| {
+        list_kitty_in(TOKEN, 100);
+        // The mock DEX asks two native units per token
+        assert_noop!(
+            Kitties::buy_kitty(Origin::signed(BOB), KITTY, 100, Some((NATIVE, 199))),
+            DispatchError::Other("ExcessiveInputAmount")
+        );
+        assert_ok!(Kitties::buy_kitty(Origin::signed(BOB), KITTY, 100, Some((NATIVE, 200))));
+
+        assert_eq!(pallet::Kitties::<Test>::get(KITTY).unwrap().owner, BOB);
+        assert_eq!(MockDex::balance(TOKEN, &ALICE), 100);
+        assert_eq!(MockDex::balance(TOKEN, &BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), 800);
+    });
+}
+
+// Paying in the listing asset needs no swap
+#[test]
+fn buy_kitty_paying_in_listing_asset_skips_swap() {
+    new_test_ext().execute_with(|| {
+        list_kitty(100);
+        assert_ok!(Kitties::buy_kitty(Origin::signed(BOB), KITTY, 100, Some((NATIVE, 0))));
+        assert_eq!(Balances::free_balance(BOB), 900);
+    });
+}
+
+// Underpaying fails and leaves the kitty with the seller
+#[test]
+fn buy_kitty_below_asking_price_fails() {
+    new_test_ext().execute_with(|| {
+        list_kitty(100);
+        assert_noop!(
+            Kitties::buy_kitty(Origin::signed(BOB), KITTY, 99, None),
+            Error::<Test>::BidPriceTooLow
+        );
+    });
+}
+
+// Buying your own kitty fails
+#[test]
+fn buy_kitty_from_self_fails() {
+    new_test_ext().execute_with(|| {
+        list_kitty(100);
+        assert_noop!(
+            Kitties::buy_kitty(Origin::signed(ALICE), KITTY, 100, None),
+            Error::<Test>::TransferToSelf
+        );
+    });
+}
+
+// A kitty without a price can't be bought
+#[test]
+fn buy_kitty_not_for_sale_fails() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Kitties::mint(&ALICE, KITTY, Gender::Female));
+        assert_noop!(
+            Kitties::buy_kitty(Origin::signed(BOB), KITTY, 100, None),
+            Error::<Test>::NotForSale
+        );
+        assert_eq!(KittiesOwned::<Test>::get(ALICE).into_inner(), vec![KITTY]);
+    });
+}
+
+const MALE: [u8; 16] = [2; 16]; // The DNA of Alice's male kitty
+const FEMALE: [u8; 16] = [3; 16]; // The DNA of Alice's female kitty
+
+// Mint a breeding pair for Alice
+fn mint_pair() {
+    assert_ok!(Kitties::mint(&ALICE, MALE, Gender::Male));
+    assert_ok!(Kitties::mint(&ALICE, FEMALE, Gender::Female));
+}
+
+// Breeding mints a child from the parents' DNA, charges the fee and rests the parents
+#[test]
+fn breed_kitty_works() {
+    new_test_ext().execute_with(|| {
+        mint_pair();
+        assert_ok!(Kitties::breed_kitty(Origin::signed(ALICE), MALE, FEMALE));
+
+        let owned = KittiesOwned::<Test>::get(ALICE);
+        assert_eq!(owned.len(), 3);
+        let child = pallet::Kitties::<Test>::get(owned[2]).unwrap();
+        // Every bit of the child comes from one of the parents, [2; 16] and [3; 16] only differ
+        // in the lowest bit
+        assert!(child.dna.iter().all(|byte| *byte == 2 || *byte == 3));
+        assert_eq!(Balances::free_balance(ALICE), 990);
+        assert_eq!(NextBreedingAt::<Test>::get(MALE), 11);
+        assert_eq!(NextBreedingAt::<Test>::get(FEMALE), 11);
+    });
+}
+
+// Two kitties of the same gender can't breed
+#[test]
+fn breed_kitty_same_gender_fails() {
+    new_test_ext().execute_with(|| {
+        mint_pair();
+        assert_ok!(Kitties::mint(&ALICE, KITTY, Gender::Female));
+        assert_noop!(
+            Kitties::breed_kitty(Origin::signed(ALICE), FEMALE, KITTY),
+            Error::<Test>::SameGender
+        );
+    });
+}
+
+// Only the owner of both parents can breed them
+#[test]
+fn breed_kitty_not_owner_fails() {
+    new_test_ext().execute_with(|| {
+        mint_pair();
+        assert_noop!(
+            Kitties::breed_kitty(Origin::signed(BOB), MALE, FEMALE),
+            Error::<Test>::NotOwner
+        );
+    });
+}
+
+// Parents can breed again only after the cooldown
+#[test]
+fn breed_kitty_cooldown() {
+    new_test_ext().execute_with(|| {
+        mint_pair();
+        assert_ok!(Kitties::breed_kitty(Origin::signed(ALICE), MALE, FEMALE));
+        assert_noop!(
+            Kitties::breed_kitty(Origin::signed(ALICE), MALE, FEMALE),
+            Error::<Test>::BreedingCooldown
+        );
+
+        System::set_block_number(11);
+        assert_ok!(Kitties::breed_kitty(Origin::signed(ALICE), MALE, FEMALE));
+    });
+}
+
+const CHARLIE: u128 = 3; // The second bidder
+
+// Advance to block `n`, settling the auctions ending on the way
+fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        Kitties::on_initialize(System::block_number());
+    }
+}
+
+// The highest bid wins an English auction, outbid reserves are refunded
+#[test]
+fn english_auction_settles_to_highest_bidder() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Kitties::mint(&ALICE, KITTY, Gender::Female));
+        assert_ok!(Kitties::create_auction(
+            Origin::signed(ALICE),
+            KITTY,
+            AuctionKind::English { min_bid: 100 },
+            10
+        ));
+
+        assert_noop!(Kitties::bid(Origin::signed(BOB), KITTY, 99), Error::<Test>::BidPriceTooLow);
+        assert_ok!(Kitties::bid(Origin::signed(BOB), KITTY, 100));
+        assert_eq!(Balances::reserved_balance(BOB), 100);
+        assert_noop!(Kitties::bid(Origin::signed(CHARLIE), KITTY, 100), Error::<Test>::BidPriceTooLow);
+        assert_ok!(Kitties::bid(Origin::signed(CHARLIE), KITTY, 150));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::reserved_balance(CHARLIE), 150);
+
+        // The kitty is locked while the auction runs
+        assert_noop!(
+            Kitties::transfer(Origin::signed(ALICE), BOB, KITTY),
+            Error::<Test>::KittyInAuction
+        );
+
+        run_to_block(11);
+        assert_eq!(pallet::Kitties::<Test>::get(KITTY).unwrap().owner, CHARLIE);
+        assert!(Auctions::<Test>::get(KITTY).is_none());
+        assert_eq!(Balances::free_balance(ALICE), 1_150);
+        assert_eq!(Balances::free_balance(BOB), 1_000);
+        assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+        assert_eq!(Balances::free_balance(CHARLIE), 850);
+        assert_noop!(Kitties::bid(Origin::signed(BOB), KITTY, 200), Error::<Test>::NoAuction);
+    });
+}
+
+// An auction without bids leaves the kitty with the seller
+#[test]
+fn auction_without_bids_keeps_kitty() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Kitties::mint(&ALICE, KITTY, Gender::Female));
+        assert_ok!(Kitties::create_auction(
+            Origin::signed(ALICE),
+            KITTY,
+            AuctionKind::English { min_bid: 100 },
+            10
+        ));
+
+        run_to_block(11);
+        assert_eq!(pallet::Kitties::<Test>::get(KITTY).unwrap().owner, ALICE);
+        assert!(Auctions::<Test>::get(KITTY).is_none());
+    });
+}
+
+// The first bid covering the falling price wins a Dutch auction, at the current price
+#[test]
+fn dutch_auction_sells_at_current_price() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Kitties::mint(&ALICE, KITTY, Gender::Female));
+        assert_ok!(Kitties::create_auction(
+            Origin::signed(ALICE),
+            KITTY,
+            AuctionKind::Dutch { start_price: 200, floor_price: 100 },
+            10
+        ));
+
+        // Halfway through the price is 150
+        run_to_block(6);
+        assert_noop!(Kitties::bid(Origin::signed(BOB), KITTY, 149), Error::<Test>::BidPriceTooLow);
+        assert_ok!(Kitties::bid(Origin::signed(BOB), KITTY, 180));
+
+        assert_eq!(pallet::Kitties::<Test>::get(KITTY).unwrap().owner, BOB);
+        assert_eq!(Balances::free_balance(ALICE), 1_150);
+        assert_eq!(Balances::free_balance(BOB), 850);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+    });
+}
+
+// A Dutch bid fails, leaving the auction open, if the bidder can't own the kitty
+#[test]
+fn dutch_bid_fails_when_kitty_cannot_be_transferred() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Kitties::mint(&ALICE, KITTY, Gender::Female));
+        assert_ok!(Kitties::create_auction(
+            Origin::signed(ALICE),
+            KITTY,
+            AuctionKind::Dutch { start_price: 200, floor_price: 100 },
+            10
+        ));
+        for i in 0..MaxKittiesOwned::get() as u8 {
+            assert_ok!(Kitties::mint(&BOB, [100 + i; 16], Gender::Male));
+        }
+
+        assert_noop!(Kitties::bid(Origin::signed(BOB), KITTY, 200), Error::<Test>::TooManyOwned);
+        assert_eq!(pallet::Kitties::<Test>::get(KITTY).unwrap().owner, ALICE);
+        assert!(Auctions::<Test>::get(KITTY).is_some());
+
+        assert_ok!(Kitties::bid(Origin::signed(CHARLIE), KITTY, 200));
+        assert_eq!(pallet::Kitties::<Test>::get(KITTY).unwrap().owner, CHARLIE);
+        assert_eq!(Balances::free_balance(ALICE), 1_200);
+    });
+}
+
+// An English auction whose reserved bid was slashed doesn't sell, the rest of the bid is refunded
+#[test]
+fn english_auction_with_slashed_bid_keeps_kitty() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Kitties::mint(&ALICE, KITTY, Gender::Female));
+        assert_ok!(Kitties::create_auction(
+            Origin::signed(ALICE),
+            KITTY,
+            AuctionKind::English { min_bid: 100 },
+            10
+        ));
+        assert_ok!(Kitties::bid(Origin::signed(BOB), KITTY, 100));
+        let _ = Balances::slash_reserved(&BOB, 40);
+
+        run_to_block(11);
+        assert_eq!(pallet::Kitties::<Test>::get(KITTY).unwrap().owner, ALICE);
+        assert!(KittiesOwned::<Test>::get(BOB).is_empty());
+        assert!(Auctions::<Test>::get(KITTY).is_none());
+        assert_eq!(Balances::free_balance(ALICE), 1_000);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), 960);
+    });
+}
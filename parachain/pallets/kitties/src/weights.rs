@@ -0,0 +1,173 @@
+//! Placeholder weights for pallet_kitties
+//!
+//! These weights were NOT produced by benchmarking: the base weights and component slopes are
+//! hand estimates and the storage weights follow the reads and writes listed above each function.
+//! `bid` is charged for a Dutch auction bid, which sells the kitty right away, `s` is 1 when
+//! `buy_kitty` swaps the payment through the DEX and `a` is the number of auctions settled by
+//! `on_initialize`. Replace this file on reference hardware with the output of
+//!
+//! ```text
+//! ./target/release/parachain-node benchmark pallet --chain=dev --execution=wasm \
+//!     --wasm-execution=compiled --pallet=pallet_kitties --extrinsic='*' --steps=50 --repeat=20 \
+//!     --template=.maintain/frame-weight-template.hbs --output=pallets/kitties/src/weights.rs
+//! ```
+//!
+//! from a node built with `--features runtime-benchmarks`.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use frame_support::sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_kitties.
+pub trait WeightInfo {
+	fn create_kitty() -> Weight;
+	fn transfer() -> Weight;
+	fn set_price() -> Weight;
+	fn breed_kitty() -> Weight;
+	fn create_auction() -> Weight;
+	fn bid() -> Weight;
+	fn buy_kitty(s: u32, ) -> Weight;
+	fn on_initialize(a: u32, ) -> Weight;
+}
+
+/// Placeholder weights for pallet_kitties, see the module documentation.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties CountForKitties (r:1 w:1)
+	// Storage: Kitties KittiesOwned (r:1 w:1)
+	fn create_kitty() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittiesOwned (r:2 w:2)
+	fn transfer() -> Weight {
+		(32_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties Auctions (r:1 w:0)
+	fn set_price() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties Kitties (r:3 w:1)
+	// Storage: Kitties NextBreedingAt (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	// Storage: Kitties CountForKitties (r:1 w:1)
+	// Storage: Kitties KittiesOwned (r:1 w:1)
+	fn breed_kitty() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties Auctions (r:1 w:1)
+	// Storage: Kitties AuctionsEndingAt (r:1 w:1)
+	fn create_auction() -> Weight {
+		(28_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Kitties Auctions (r:1 w:1)
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties KittiesOwned (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Kitties AuctionsEndingAt (r:1 w:1)
+	fn bid() -> Weight {
+		(52_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittiesOwned (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Dex LiquidityPools (r:1 w:1), if swapping
+	// Storage: Timestamp Now (r:1 w:0), if swapping
+	// Storage: Dex Observations (r:1 w:1), if swapping
+	// Storage: Assets Asset (r:2 w:2), if swapping
+	// Storage: Assets Account (r:4 w:4), if swapping
+	// Storage: Dex LastTrades (r:0 w:1), if swapping
+	fn buy_kitty(s: u32, ) -> Weight {
+		(44_000_000 as Weight)
+			.saturating_add((65_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads((9 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes((9 as Weight).saturating_mul(s as Weight)))
+	}
+	// Storage: Kitties AuctionsEndingAt (r:1 w:1)
+	// Storage: Kitties Auctions (r:1 w:1)
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties KittiesOwned (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	fn on_initialize(a: u32, ) -> Weight {
+		(4_000_000 as Weight)
+			.saturating_add((46_000_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(a as Weight)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn create_kitty() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn transfer() -> Weight {
+		(32_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn set_price() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn breed_kitty() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn create_auction() -> Weight {
+		(28_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn bid() -> Weight {
+		(52_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn buy_kitty(s: u32, ) -> Weight {
+		(44_000_000 as Weight)
+			.saturating_add((65_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((9 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((9 as Weight).saturating_mul(s as Weight)))
+	}
+	fn on_initialize(a: u32, ) -> Weight {
+		(4_000_000 as Weight)
+			.saturating_add((46_000_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((6 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(a as Weight)))
+	}
+}
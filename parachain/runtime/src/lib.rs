@@ -15,7 +15,10 @@ use sp_api::impl_runtime_apis;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, Verify},
+	traits::{
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount,
+		Verify,
+	},
 	transaction_validity::{TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
 };
@@ -76,6 +79,9 @@ pub type Hash = sp_core::H256;
 /// An index to a block.
 pub type BlockNumber = u32;
 
+/// An asset which can be pooled in the DEX: the native currency or an asset of `Assets`.
+pub type DexAssetId = pallet_dex::MultiAssetId<u32>;
+
 /// The address format for describing accounts.
 pub type Address = MultiAddress<AccountId, ()>;
 
@@ -486,7 +492,21 @@ impl pallet_assets::Config for Runtime {
 parameter_types! {
 	pub const Decks: PalletId = PalletId(*b"dotdecks");
 	pub const TokenMinimumBalance: u32 = 1; // Must be greater than 0 (existential deposit)
+	pub const MinimumLiquidity: u32 = 1_000; // Must be at least TokenMinimumBalance
 	pub const TokenDecimals: u8 = 12;
+	pub const DefaultFee: Permill = Permill::from_parts(3_000); // 0.3%
+	pub FeeTiers: Vec<Permill> = vec![
+		Permill::from_parts(500), // 0.05%
+		Permill::from_parts(3_000), // 0.3%
+		Permill::from_parts(10_000), // 1%
+	];
+	pub const DexTreasuryPalletId: PalletId = PalletId(*b"py/dextr");
+	pub DexTreasuryAccount: AccountId = DexTreasuryPalletId::get().into_account_truncating();
+	pub const ProtocolFeeDenominator: u32 = 6; // 1/6 of fee growth, as in Uniswap V2
+	pub const MaxHops: u32 = 4; // Longest swap path, in assets
+	pub NativeSymbol: Vec<u8> = b"UNIT".to_vec();
+	pub const PoolCreationDeposit: Balance = 10 * UNIT;
+	pub const MaxObservations: u32 = 24;
 }
 
 impl pallet_dex::Config for Runtime {
@@ -494,16 +514,85 @@ impl pallet_dex::Config for Runtime {
 	type AssetId = u32;
 	type Tokens = Assets;
 	type PalletId = Decks;
+	type DefaultFee = DefaultFee;
+	type FeeTiers = FeeTiers;
+	type ProtocolFeeReceiver = DexTreasuryAccount;
+	type ProtocolFeeDenominator = ProtocolFeeDenominator;
+	type ProtocolFeeOrigin = EnsureRoot<AccountId>;
+	type MaxHops = MaxHops;
 	type LpTokenMinimumBalance = TokenMinimumBalance;
+	type MinimumLiquidity = MinimumLiquidity;
 	type LpTokenDecimals = TokenDecimals;
 	type NativeCurrency = Balances;
-	type NativeTokenId = ();
+	type NativeSymbol = NativeSymbol;
+	type PoolCreationDeposit = PoolCreationDeposit;
+	type Time = Timestamp;
+	type MaxObservations = MaxObservations;
+	type WeightInfo = pallet_dex::weights::SubstrateWeight<Runtime>;
 	
 	fn exists(id: Self::AssetId) -> bool {
 		Assets::maybe_total_supply(id).is_some()
 	}
 }
 
+/// Randomness from the hashes of the last 81 blocks. Collators can predict and bias it, which
+/// is acceptable for kitty DNA but not for anything of value.
+impl pallet_randomness_collective_flip::Config for Runtime {}
+
+parameter_types! {
+	pub const MaxKittiesOwned: u32 = 100;
+	pub const BreedingCooldown: BlockNumber = 10 * MINUTES;
+	pub const BreedingFee: Balance = 10 * MILLIUNIT;
+	pub const MaxAuctionsPerBlock: u32 = 16;
+}
+
+impl pallet_kitties::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type MaxKittiesOwned = MaxKittiesOwned;
+	type KittyRandomness = RandomnessCollectiveFlip;
+	type BreedingCooldown = BreedingCooldown;
+	type BreedingFee = BreedingFee;
+	type OnBreedingFee = ();
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type Dex = Dex;
+	type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = KittiesBenchmarkHelper;
+}
+
+/// Lists the benchmarked kitties in the native currency and pays for them in a token, swapped
+/// through a new native pool
+#[cfg(feature = "runtime-benchmarks")]
+pub struct KittiesBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_kitties::BenchmarkHelper<AccountId, DexAssetId, Balance> for KittiesBenchmarkHelper {
+	fn setup_market(who: &AccountId, price: Balance) -> (DexAssetId, DexAssetId) {
+		use frame_support::traits::{
+			tokens::fungibles::{Create, Mutate},
+			Currency,
+		};
+
+		// Clear of the assets of the genesis config
+		const TOKEN: u32 = 2_000;
+		let liquidity = price.saturating_mul(1_000);
+		let funds = liquidity.saturating_mul(2);
+		let _ = <Balances as Currency<AccountId>>::deposit_creating(
+			who,
+			funds.saturating_add(PoolCreationDeposit::get()),
+		);
+		<Assets as Create<AccountId>>::create(TOKEN, who.clone(), true, 1).unwrap();
+		<Assets as Mutate<AccountId>>::mint_into(TOKEN, who, funds).unwrap();
+
+		let (native, token) = (DexAssetId::Native, DexAssetId::Asset(TOKEN));
+		let origin = Origin::signed(who.clone());
+		Dex::create_pool(origin.clone(), native, token, None).unwrap();
+		Dex::add_liquidity(origin, liquidity, native, liquidity, token, None, 0, 0).unwrap();
+		(native, token)
+	}
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
 	pub enum Runtime where
@@ -518,6 +607,7 @@ construct_runtime!(
 		} = 1,
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent} = 2,
 		ParachainInfo: parachain_info::{Pallet, Storage, Config} = 3,
+		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage} = 4,
 
 		// Monetary stuff.
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 10,
@@ -540,7 +630,10 @@ construct_runtime!(
 		Assets: pallet_assets = 51,
 
 		// Dex
-		Dex: pallet_dex::{Pallet, Call, Storage, Event<T>} = 52,
+		Dex: pallet_dex::{Pallet, Call, Storage, Config<T>, Event<T>} = 52,
+
+		// Kitties
+		Kitties: pallet_kitties::{Pallet, Call, Storage, Config<T>, Event<T>} = 53,
 	}
 );
 
@@ -557,6 +650,8 @@ mod benches {
 		[pallet_timestamp, Timestamp]
 		[pallet_collator_selection, CollatorSelection]
 		[cumulus_pallet_xcmp_queue, XcmpQueue]
+		[pallet_dex, Dex]
+		[pallet_kitties, Kitties]
 	);
 }
 
@@ -661,6 +756,68 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_dex_runtime_api::DexApi<Block, DexAssetId, Balance, AccountId> for Runtime {
+		fn get_reserves(a: DexAssetId, b: DexAssetId, fee: Option<Permill>) -> Option<(Balance, Balance)> {
+			Dex::get_pool(a, b, fee).ok().map(|pool| pool.reserves_for(a))
+		}
+
+		fn quote_exact_in(path: Vec<DexAssetId>, amount: Balance, fee: Option<Permill>) -> Option<Balance> {
+			Dex::get_amounts_out(amount, &path, fee).ok().and_then(|amounts| amounts.last().copied())
+		}
+
+		fn quote_exact_out(path: Vec<DexAssetId>, amount: Balance, fee: Option<Permill>) -> Option<Balance> {
+			Dex::get_amounts_in(amount, &path, fee).ok().and_then(|amounts| amounts.first().copied())
+		}
+
+		fn list_pools() -> Vec<pallet_dex_runtime_api::PoolInfo<DexAssetId, Balance, AccountId>> {
+			Dex::pools()
+				.into_iter()
+				.map(|pool| pallet_dex_runtime_api::PoolInfo {
+					pair: pool.pair,
+					fee: pool.fee,
+					lp_token: pallet_dex::MultiAssetId::Asset(pool.id),
+					reserves: pool.reserves(),
+					lp_total_supply: Assets::total_supply(pool.id),
+					account: pool.account,
+				})
+				.collect()
+		}
+
+		fn lp_token_of(a: DexAssetId, b: DexAssetId, fee: Option<Permill>) -> Option<DexAssetId> {
+			Dex::get_pool(a, b, fee).ok().map(|pool| pallet_dex::MultiAssetId::Asset(pool.id))
+		}
+
+		fn position(
+			account: AccountId,
+			a: DexAssetId,
+			b: DexAssetId,
+			fee: Option<Permill>,
+		) -> Option<pallet_dex_runtime_api::Position<Balance>> {
+			let pool = Dex::get_pool(a, b, fee).ok()?;
+			let (lp_balance, amount_0, amount_1) = Dex::position(&pool, &account).ok()?;
+			let amounts = if a == pool.pair.0 { (amount_0, amount_1) } else { (amount_1, amount_0) };
+			Some(pallet_dex_runtime_api::Position {
+				lp_balance,
+				amounts,
+				lp_total_supply: Assets::total_supply(pool.id),
+			})
+		}
+
+		fn last_trade(
+			a: DexAssetId,
+			b: DexAssetId,
+			fee: Option<Permill>,
+		) -> Option<pallet_dex_runtime_api::Trade<AccountId, DexAssetId, Balance>> {
+			Dex::last_trade(a, b, fee).map(|(who, token_in, amount_in, token_out, amount_out)| {
+				pallet_dex_runtime_api::Trade { who, token_in, amount_in, token_out, amount_out }
+			})
+		}
+
+		fn pool_storage_keys(a: DexAssetId, b: DexAssetId, fee: Option<Permill>) -> Vec<Vec<u8>> {
+			Dex::pool_storage_keys(a, b, fee)
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)